@@ -7,17 +7,25 @@ extern crate enocean;
 fn main() {
     // Just to show how much enocean serial packets were received
     let mut nb_received = 0;
-    // For now, this variable is hardcoded
-    let port_name = "/dev/ttyUsb300".to_string(); //Get this from env?
-                                                  // let port_name = "/dev/tty.usbserial-FTWTOH0A".to_string(); //Get this from env?
-                                                  // Communication channels based on MPSC (1 to send, 1 to receive esp3 packets)
+    // Serial port of the gateway : first argument, else the ENOCEAN_PORT variable, else the first detected EnOcean gateway
+    let port_name = std::env::args().nth(1)
+        .or_else(|| std::env::var("ENOCEAN_PORT").ok())
+        .or_else(|| enocean::port::find_gateway().map(|gateway| gateway.port_name));
+    let port_name = match port_name {
+        Some(port_name) => port_name,
+        None => {
+            eprintln!("No EnOcean gateway detected, give its serial port as argument or in ENOCEAN_PORT");
+            return;
+        }
+    };
+    // Communication channels based on MPSC (1 to send, 1 to receive esp3 packets)
     let (enocean_emiter, enocean_event_receiver) = mpsc::channel();
     let (_enocean_command_receiver, enocean_commander) = mpsc::channel();
 
     // Create a thread to interact (both ways) with serial port
     // The interaction is achieved thanks to 2 channels (std::sync lib)
     let _enocean_listener = thread::spawn(move || {
-        if let Err(e) = enocean::communicator::start(port_name, enocean_emiter, enocean_commander) {
+        if let Err(e) = enocean::communicator::listen(port_name, enocean_emiter, enocean_commander) {
             println!("ERROR when oopening serial port : {:?}", e);
         }
     });
@@ -28,7 +36,8 @@ fn main() {
         let message = enocean_event_receiver.try_recv();
         match message {
             Ok(esp3_packet) => {
-                println! {"Received ESP3 packet : {}", esp3_packet};
+                enocean::enocean::print_esp3(&esp3_packet);
+                println!("{}", enocean::enocean::format_telegram_hex(&Vec::from(&esp3_packet)));
 
                 nb_received = nb_received + 1;
                 println!("---> RECEIVED : {}", nb_received);
@@ -9,16 +9,25 @@ fn main() {
     let mut nb_received = 0;
     // Just to show how much enocean serial packets were sended
     let mut nb_sended = 0;
-    // For now, this variable is hardcoded
-    let port_name = "/dev/ttyUsb300".to_string(); //Get this from env?
-                                                  // Communication channels based on MPSC (1 to send, 1 to receive esp3 packets)
+    // Serial port of the gateway : first argument, else the ENOCEAN_PORT variable, else the first detected EnOcean gateway
+    let port_name = std::env::args().nth(1)
+        .or_else(|| std::env::var("ENOCEAN_PORT").ok())
+        .or_else(|| enocean::port::find_gateway().map(|gateway| gateway.port_name));
+    let port_name = match port_name {
+        Some(port_name) => port_name,
+        None => {
+            eprintln!("No EnOcean gateway detected, give its serial port as argument or in ENOCEAN_PORT");
+            return;
+        }
+    };
+    // Communication channels based on MPSC (1 to send, 1 to receive esp3 packets)
     let (enocean_emiter, enocean_event_receiver) = mpsc::channel();
     let (enocean_command_receiver, enocean_commander) = mpsc::channel();
 
     // Create a thread to interact (both ways) with serial port
     // The interaction is achieved thanks to 2 channels (std::sync lib)
     let _enocean_listener = thread::spawn(move || {
-        enocean::communicator::start(port_name, enocean_emiter, enocean_commander)
+        enocean::communicator::listen(port_name, enocean_emiter, enocean_commander)
             .unwrap(); // crash the thread if the communicator fails
     });
 
@@ -45,20 +54,20 @@ fn main() {
 
     // If command is valid, create a thread to send it periodically
     let _command_emiter = thread::spawn(move || loop {
-        match enocean_command_receiver.send(power_query.clone()) {
+        match enocean_command_receiver.send(power_query.clone().into()) {
             Ok(_t) => {}
             Err(e) => eprintln!("erreur lors de l'envoi : {:?}", e),
         }
         nb_sended = nb_sended + 1;
         thread::sleep(Duration::from_millis(1000));
-        match enocean_command_receiver.send(power_off.clone()) {
+        match enocean_command_receiver.send(power_off.clone().into()) {
             Ok(_t) => {}
             Err(e) => eprintln!("erreur lors de l'envoi : {:?}", e),
         }
         nb_sended = nb_sended + 1;
         thread::sleep(Duration::from_millis(1000));
 
-        match enocean_command_receiver.send(power_on.clone()) {
+        match enocean_command_receiver.send(power_on.clone().into()) {
             Ok(_t) => {}
             Err(e) => eprintln!("erreur lors de l'envoi : {:?}", e),
         }
@@ -75,7 +84,7 @@ fn main() {
         let message = enocean_event_receiver.try_recv();
         match message {
             Ok(esp3_packet) => {
-                println! {"Received ESP3 packet : {}", esp3_packet};
+                enocean::enocean::print_esp3(&esp3_packet);
 
                 nb_received = nb_received + 1;
                 println!("---> RECEIVED : {}", nb_received);
@@ -4,142 +4,521 @@ use serialport;
 use std::time::Duration;
 
 use std::io;
-use std::io::Write;
+use std::io::{BufRead, BufReader, Read, Write};
 
 use std::sync::mpsc;
 
-use crate::ParseEspErrorKind;
+use crate::dedup::Deduplicator;
+use crate::frame::ESP3Frame;
+use crate::{CommunicatorError, FrameReadError, ParseEspResult};
+use std::borrow::Borrow;
+use log::{debug, error, info, trace, warn};
 
+/// How long we wait for the end of a telegram once its first bytes are received
+const FRAME_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How [`start`] (and [`run_reconnecting`]) reopens the port after a read error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReconnectPolicy {
+    /// How many times we try to reopen the port, at most
+    pub attempts: u32,
+    /// Delay before reopening, doubled after each failed attempt
+    pub backoff: Duration,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        ReconnectPolicy { attempts: 3, backoff: Duration::from_millis(500) }
+    }
+}
+
+/// A packet to write to the port, see [`listen`]
+#[derive(Debug, Clone)]
+pub enum OutgoingCommand {
+    Esp3(ESP3),
+    Frame(ESP3Frame),
+    /// Raw bytes of a whole ESP3 packet (eg. a captured telegram), checked with [`verify_esp3_crc`] before being written
+    Raw(Vec<u8>),
+}
+
+impl From<ESP3> for OutgoingCommand {
+    fn from(packet: ESP3) -> Self {
+        OutgoingCommand::Esp3(packet)
+    }
+}
+
+impl From<ESP3Frame> for OutgoingCommand {
+    fn from(frame: ESP3Frame) -> Self {
+        OutgoingCommand::Frame(frame)
+    }
+}
+
+impl From<Vec<u8>> for OutgoingCommand {
+    fn from(bytes: Vec<u8>) -> Self {
+        OutgoingCommand::Raw(bytes)
+    }
+}
+
+impl OutgoingCommand {
+    /// Bytes to write to the port
+    fn to_bytes(&self) -> ParseEspResult<Vec<u8>> {
+        match self {
+            OutgoingCommand::Esp3(packet) => Ok(Vec::from(packet)),
+            OutgoingCommand::Frame(frame) => {
+                let bytes: &[u8] = frame.borrow();
+                Ok(bytes.to_vec())
+            }
+            OutgoingCommand::Raw(bytes) => verify_esp3_crc(bytes).map(|()| bytes.clone()),
+        }
+    }
+}
+
+/// Open the serial port `port_name` and run the read/send loop :
+/// incoming ESP3 packets are sent through `enocean_event`, and packets received on `enocean_command` are written to the port.
+/// Returns `Ok` once the sender of `enocean_command` is dropped, and an error if `enocean_event` is closed,
+/// or if the port could not be reopened after a read error (see [`ReconnectPolicy`]).
+pub fn listen(
+    port_name: String,
+    enocean_event: mpsc::Sender<ESP3>,
+    enocean_command: mpsc::Receiver<OutgoingCommand>,
+) -> Result<(), CommunicatorError> {
+    start(port_name, enocean_event, enocean_command)
+}
+
+/// Same as [`listen`].
 pub fn start(
     port_name: String,
     enocean_event: mpsc::Sender<ESP3>,
-    enocean_command: mpsc::Receiver<ESP3>,
-) -> Result<(), std::io::Error> {
-    // Set settings as mentioned in ESP3
+    enocean_command: mpsc::Receiver<OutgoingCommand>,
+) -> Result<(), CommunicatorError> {
+    let serial_port = open_port(&port_name).map_err(|e| {
+        error!("Failed to open \"{}\". Error ({:?}): {}", port_name, e.kind(), e);
+        let gateways = crate::port::list_gateways();
+        match gateways.len() {
+            0 => info!("No ports found."),
+            1 => info!("Available port :  "),
+            n => info!("Available ports ({}):", n),
+        };
+        for gateway in gateways {
+            info!("  {}", gateway.port_name);
+        }
+        std::io::Error::new(std::io::ErrorKind::NotConnected, e.to_string())
+    })?;
+
+    info!("Receiving data on {}:", &port_name);
+    let reopen = || {
+        open_port(&port_name).map_err(|e| CommunicatorError::IO(io::Error::new(io::ErrorKind::NotConnected, e.to_string())))
+    };
+    run_reconnecting(serial_port, reopen, enocean_event, enocean_command, ReconnectPolicy::default())
+}
 
+/// Open `port_name` with the settings of ESP3
+fn open_port(port_name: &str) -> Result<Box<dyn serialport::SerialPort>, serialport::Error> {
     let baud_rate = 57600;
-    let mut serial_port = serialport::new(&port_name, baud_rate)
+    serialport::new(port_name, baud_rate)
         .timeout(Duration::from_millis(100))
         .data_bits(serialport::DataBits::Eight)
         .parity(serialport::Parity::None)
         .stop_bits(serialport::StopBits::One)
         .flow_control(serialport::FlowControl::None)
         .open()
-        .or_else(|e| {
-            eprintln!("Failed to open \"{}\". Error: {}", port_name, e);
-            if let Ok(ports) = serialport::available_ports() {
-                match ports.len() {
-                    0 => println!("No ports found."),
-                    1 => println!("Available port :  "),
-                    n => println!("Available ports ({}):", n),
-                };
-                for p in ports {
-                    println!("  {}", p.port_name);
-                }
-            } else {
-                print!("Error listing serial ports");
+}
+
+/// Same as [`run`], but when reading `serial_port` fails (eg. a brief USB disconnection), a new port is opened with `reopen`
+/// and the loop goes on with it. Reopening is attempted as set by `policy`, with a warning for each attempt,
+/// and the error is returned once every attempt failed. A successful reopening resets the attempt count.
+pub fn run_reconnecting<P: Read + Write>(
+    serial_port: P,
+    mut reopen: impl FnMut() -> Result<P, CommunicatorError>,
+    enocean_event: mpsc::Sender<ESP3>,
+    enocean_command: mpsc::Receiver<OutgoingCommand>,
+    policy: ReconnectPolicy,
+) -> Result<(), CommunicatorError> {
+    let mut deduplicator = None;
+    let mut serial_port = serial_port;
+    loop {
+        let mut error = match run_loop(serial_port, &enocean_event, &enocean_command, &mut deduplicator) {
+            Err(e @ (CommunicatorError::IO(_) | CommunicatorError::PortDisconnected)) => e,
+            result => return result,
+        };
+        let mut backoff = policy.backoff;
+        let mut attempt = 1;
+        serial_port = loop {
+            if attempt > policy.attempts {
+                error!("Could not reopen the serial port after {} attempts, stopping", policy.attempts);
+                return Err(error);
             }
-            Err(std::io::Error::new(std::io::ErrorKind::NotConnected, e.to_string()))            
-        })?;
+            warn!("Serial port error ({}), reopening it in {:?} (attempt {}/{})", error, backoff, attempt, policy.attempts);
+            std::thread::sleep(backoff);
+            match reopen() {
+                Ok(port) => break port,
+                Err(e) => error = e,
+            }
+            backoff *= 2;
+            attempt += 1;
+        };
+        info!("Serial port reopened");
+    }
+}
+
+/// Run the read/send loop of [`listen`] on an already opened port (or anything which can be read and written, eg. a [`MockPort`](crate::mock::MockPort)).
+/// Stops like [`listen`].
+pub fn run(
+    serial_port: impl Read + Write,
+    enocean_event: mpsc::Sender<ESP3>,
+    enocean_command: mpsc::Receiver<OutgoingCommand>,
+) -> Result<(), CommunicatorError> {
+    run_with(serial_port, enocean_event, enocean_command, None)
+}
 
+/// Same as [`run`], but telegrams which are not new for `deduplicator` (if any) are dropped instead of sent through `enocean_event`.
+pub fn run_with(
+    serial_port: impl Read + Write,
+    enocean_event: mpsc::Sender<ESP3>,
+    enocean_command: mpsc::Receiver<OutgoingCommand>,
+    mut deduplicator: Option<Deduplicator>,
+) -> Result<(), CommunicatorError> {
+    run_loop(serial_port, &enocean_event, &enocean_command, &mut deduplicator)
+}
 
-    let mut serial_buf: Vec<u8> = vec![0; 100];
-    let mut incomplete_serial_buf: Option<Vec<u8>> = None;
-    eprintln!("Receiving data on {}:", &port_name);
+/// The read/send loop of [`run_with`], borrowing what is kept when the port is reopened
+fn run_loop(
+    serial_port: impl Read + Write,
+    enocean_event: &mpsc::Sender<ESP3>,
+    enocean_command: &mpsc::Receiver<OutgoingCommand>,
+    deduplicator: &mut Option<Deduplicator>,
+) -> Result<(), CommunicatorError> {
+    // Frame synchronization and CRC checks are done by ESP3Frame::read_from_timeout
+    let mut reader = BufReader::new(serial_port);
 
     // ENOCEAN COMMAND SEND (if any)
     loop {
-        let packet_to_send = enocean_command.try_recv();
-        match packet_to_send {
-            Ok(packet) => {
-                println!("sending packet : {:?}", packet);
-                // Convert ESP3 to u8
-                let bytes_to_send = Vec::from(&packet);
-                match serial_port.write_all(&bytes_to_send[..]) {
-                    Ok(()) => {
-                        print!(".");
-                        std::io::stdout().flush().unwrap();
-                    }
-                    Err(ref e) if e.kind() == io::ErrorKind::TimedOut => (),
-                    Err(e) => eprintln!("{:?}", e),
+        let command = match enocean_command.try_recv() {
+            Ok(packet) => Some(packet),
+            Err(mpsc::TryRecvError::Empty) => None,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                info!("Command channel closed, stopping");
+                return Ok(());
+            }
+        };
+        if let Some(command) = command {
+            debug!("sending packet : {:?}", command);
+            let bytes_to_send = match command.to_bytes() {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    warn!("Invalid packet not sent ({:?}) : {}", e.kind, e);
+                    continue;
                 }
+            };
+            match reader.get_mut().write_all(&bytes_to_send[..]) {
+                Ok(()) => trace!("packet sent"),
+                Err(ref e) if e.kind() == io::ErrorKind::TimedOut => (),
+                Err(e) => error!("Error while sending packet ({:?}) : {}", e.kind(), e),
             }
-            Err(_) => {}
         }
         // USB300 MESSAGE RECEIVE (if any)
 
-        match serial_port.read(&mut serial_buf[..]) {
-            Ok(t) => {
-                // If we received an incomming telegram :
-                // println!("Received telegram : {:X?} ", &serial_buf[..t]);
-                match esp3_of_enocean_message(&serial_buf[..t]) {
-                    Ok(esp3_packet) => {
-                        // If we achieved to transform it into an ESP3 packet, send it to the main thread
-                        match enocean_event.send(esp3_packet.clone()) {
-                            Ok(_result) => {}
-                            Err(e) => {
-                                eprintln!(
-                                    "Erreur lors de l'envoi du packet : {:?} erreur : {:?}",
-                                    esp3_packet, e
-                                );
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        // If message was incomplete, maybe the telegram is just truncated (received in 2 differents parts)
-                        match e.kind {
-                            // If it's the "first part"
-                            ParseEspErrorKind::IncompleteMessage => {
-                                // We save it for next incomming telegram parsing
-                                // println!("Saving : {:x?}", e.packet);
-                                incomplete_serial_buf = Some(e.packet);
-                            }
-                            // If it's the "second part"
-                            ParseEspErrorKind::NoSyncByte => {
-                                match incomplete_serial_buf {
-                                    // If we have stored the first part before
-                                    Some(mut buffer) => {
-                                        buffer.extend(e.packet.iter().cloned());
-                                        // println!("REPAIRED telegram : {:X?} ", buffer);
-                                        match esp3_of_enocean_message(&buffer[..]) {
-                                            Ok(esp3_packet) => {
-                                                // send it to the main thread
-                                                match enocean_event
-                                                    .send(esp3_packet.clone())
-                                                {
-                                                    Ok(_result) => {}
-                                                    Err(e) => {
-                                                        eprintln!(
-                                                    "Erreur lors de l'envoi du packet : {:?} erreur : {:?}",
-                                                    esp3_packet, e
-                                                    );
-                                                    }
-                                                }
-                                            }
-                                            Err(e) => {
-                                                eprintln!(
-                                                    "Erreur malgré reconstruction {:?}",
-                                                    e
-                                                );
-                                            }
-                                        }
-                                        incomplete_serial_buf = None;
-                                    }
-                                    None => {}
-                                }
-                            }
-                            _ => {
-                                eprintln!("Autre erreur : {:?}", e);
-                            }
-                        }
+        // Wait (up to the port timeout) for incoming bytes, so that commands can still be sent
+        match reader.fill_buf() {
+            Ok([]) => return Err(CommunicatorError::PortDisconnected),
+            Ok(_) => (),
+            Err(ref e) if e.kind() == io::ErrorKind::TimedOut => continue,
+            Err(e) => {
+                error!("Error while trying to read serial port input buffer ({:?}) : {}", e.kind(), e);
+                return Err(read_error(e));
+            }
+        }
+
+        // A telegram may be received in several parts : wait for the end of it
+        match ESP3Frame::read_from_timeout(&mut reader, FRAME_TIMEOUT) {
+            Ok(frame) => match ESP3::from_frame(&frame) {
+                Ok(esp3_packet) if deduplicator.as_mut().is_some_and(|d| !d.is_new(&esp3_packet)) => {
+                    trace!("Dropping repeated telegram");
+                }
+                // send it to the main thread
+                Ok(esp3_packet) => {
+                    if enocean_event.send(esp3_packet).is_err() {
+                        info!("Event channel closed, stopping");
+                        return Err(CommunicatorError::ChannelClosed);
                     }
                 }
+                Err(e) => warn!("Autre erreur ({:?}) : {}", e.kind, e),
             },
-            Err(ref e) if e.kind() == io::ErrorKind::TimedOut => (),
-            Err(e) => {
-                eprintln!("Error while trying to read serial port input buffer : {:?}", e);
-                return Err(std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))
-                } ,
+            Err(FrameReadError::IOError(e)) => {
+                error!("Error while trying to read serial port input buffer ({:?}) : {}", e.kind(), e);
+                return Err(read_error(e));
+            }
+            Err(FrameReadError::EOF) => return Err(CommunicatorError::PortDisconnected),
+            Err(e) => warn!("Autre erreur : {:?}", e),
         }
     } // LOOP END
-}
\ No newline at end of file
+}
+
+/// Async version of [`listen`], on tokio channels (requires the `async` feature) :
+/// open the serial port `port_name` and run [`run_async_on`] on it.
+#[cfg(feature = "async")]
+pub async fn run_async(
+    port_name: String,
+    enocean_event: tokio::sync::mpsc::Sender<ESP3>,
+    enocean_command: tokio::sync::mpsc::Receiver<OutgoingCommand>,
+) -> Result<(), CommunicatorError> {
+    use tokio_serial::SerialPortBuilderExt;
+
+    let serial_port = tokio_serial::new(&port_name, 57600)
+        .data_bits(tokio_serial::DataBits::Eight)
+        .parity(tokio_serial::Parity::None)
+        .stop_bits(tokio_serial::StopBits::One)
+        .flow_control(tokio_serial::FlowControl::None)
+        .open_native_async()
+        .map_err(|e| {
+            error!("Failed to open \"{}\". Error ({:?}): {}", port_name, e.kind(), e);
+            io::Error::new(io::ErrorKind::NotConnected, e.to_string())
+        })?;
+
+    info!("Receiving data on {}:", &port_name);
+    run_async_on(serial_port, enocean_event, enocean_command).await
+}
+
+/// Run the read/send loop of [`run_async`] on an already opened port (or any async byte stream, eg. a `tokio::io::duplex`).
+/// Stops like [`listen`].
+#[cfg(feature = "async")]
+pub async fn run_async_on(
+    serial_port: impl tokio::io::AsyncRead + tokio::io::AsyncWrite,
+    enocean_event: tokio::sync::mpsc::Sender<ESP3>,
+    mut enocean_command: tokio::sync::mpsc::Receiver<OutgoingCommand>,
+) -> Result<(), CommunicatorError> {
+    use tokio::io::AsyncWriteExt;
+
+    let (reader, mut writer) = tokio::io::split(serial_port);
+    let mut reader = tokio::io::BufReader::new(reader);
+
+    // Reading and sending run concurrently, until one of them stops
+    let receive = async {
+        loop {
+            match ESP3Frame::read_from_async(&mut reader).await {
+                Ok(frame) => match ESP3::from_frame(&frame) {
+                    Ok(esp3_packet) => {
+                        if enocean_event.send(esp3_packet).await.is_err() {
+                            info!("Event channel closed, stopping");
+                            return Err(CommunicatorError::ChannelClosed);
+                        }
+                    }
+                    Err(e) => warn!("Autre erreur ({:?}) : {}", e.kind, e),
+                },
+                Err(FrameReadError::IOError(e)) => {
+                    error!("Error while trying to read serial port input buffer ({:?}) : {}", e.kind(), e);
+                    return Err(read_error(e));
+                }
+                Err(FrameReadError::EOF) => return Err(CommunicatorError::PortDisconnected),
+                Err(e) => warn!("Autre erreur : {:?}", e),
+            }
+        }
+    };
+    let send = async {
+        while let Some(command) = enocean_command.recv().await {
+            debug!("sending packet : {:?}", command);
+            match command.to_bytes() {
+                Ok(bytes) => writer.write_all(&bytes).await?,
+                Err(e) => warn!("Invalid packet not sent ({:?}) : {}", e.kind, e),
+            }
+        }
+        info!("Command channel closed, stopping");
+        Ok(())
+    };
+    tokio::select! {
+        result = receive => result,
+        result = send => result,
+    }
+}
+
+/// Errors telling that the port was closed or unplugged are reported as `PortDisconnected`
+fn read_error(e: io::Error) -> CommunicatorError {
+    match e.kind() {
+        io::ErrorKind::BrokenPipe | io::ErrorKind::NotConnected | io::ErrorKind::UnexpectedEof => {
+            CommunicatorError::PortDisconnected
+        }
+        _ => CommunicatorError::IO(e),
+    }
+}
+
+/// Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockPort;
+
+    // Valid message from a necklace pushbutton (EEP F6-01-01)
+    const TELEGRAM: [u8; 21] = [
+        85, 0, 7, 7, 1, 122, 246, 0, 254, 245, 143, 212, 32, 2, 255, 255, 255, 255, 48, 0, 39,
+    ];
+
+    #[test]
+    fn given_telegram_split_across_two_reads_then_reassemble_and_emit_it() {
+        let (first_part, second_part) = TELEGRAM.split_at(10);
+        let mut port = MockPort::new([first_part, second_part]);
+        let (event_sender, event_receiver) = mpsc::channel();
+        let (_command_sender, command_receiver) = mpsc::channel();
+
+        // The loop stops once the mock port has nothing more to read
+        assert!(run(&mut port, event_sender, command_receiver).is_err());
+
+        let esp3_packet = event_receiver.try_recv().unwrap();
+        assert_eq!(Vec::from(&esp3_packet), TELEGRAM.to_vec());
+        assert!(event_receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn given_two_telegrams_in_one_read_then_emit_both() {
+        let mut chunk = TELEGRAM.to_vec();
+        chunk.extend_from_slice(&TELEGRAM);
+        let mut port = MockPort::new([chunk]);
+        let (event_sender, event_receiver) = mpsc::channel();
+        let (_command_sender, command_receiver) = mpsc::channel();
+
+        assert!(run(&mut port, event_sender, command_receiver).is_err());
+
+        assert_eq!(event_receiver.try_iter().count(), 2);
+    }
+
+    #[test]
+    fn given_repeated_telegram_and_deduplicator_then_emit_it_once() {
+        let mut chunk = TELEGRAM.to_vec();
+        chunk.extend_from_slice(&TELEGRAM);
+        let mut port = MockPort::new([chunk]);
+        let (event_sender, event_receiver) = mpsc::channel();
+        let (_command_sender, command_receiver) = mpsc::channel();
+
+        let deduplicator = Deduplicator::new(Duration::from_secs(1));
+        assert!(run_with(&mut port, event_sender, command_receiver, Some(deduplicator)).is_err());
+
+        assert_eq!(event_receiver.try_iter().count(), 1);
+    }
+
+    #[test]
+    fn given_noise_and_telegram_split_at_any_byte_then_emit_it() {
+        for split in 1..TELEGRAM.len() {
+            let mut first_part = vec![0x00, 0x12, 0x34];
+            first_part.extend_from_slice(&TELEGRAM[..split]);
+            let mut port = MockPort::new([first_part, TELEGRAM[split..].to_vec()]);
+            let (event_sender, event_receiver) = mpsc::channel();
+            let (_command_sender, command_receiver) = mpsc::channel();
+
+            assert!(run(&mut port, event_sender, command_receiver).is_err());
+
+            assert_eq!(Vec::from(&event_receiver.try_recv().unwrap()), TELEGRAM.to_vec());
+        }
+    }
+
+    #[test]
+    fn given_command_then_write_it_to_the_port() {
+        let mut port = MockPort::default();
+        let (event_sender, _event_receiver) = mpsc::channel();
+        let (command_sender, command_receiver) = mpsc::channel();
+        command_sender.send(esp3_of_enocean_message(&TELEGRAM).unwrap().into()).unwrap();
+
+        assert!(run(&mut port, event_sender, command_receiver).is_err());
+        assert_eq!(port.written(), &TELEGRAM[..]);
+    }
+
+    #[test]
+    fn given_frame_and_raw_commands_then_write_valid_ones_to_the_port() {
+        let mut port = MockPort::default();
+        let (event_sender, _event_receiver) = mpsc::channel();
+        let (command_sender, command_receiver) = mpsc::channel();
+        let frame = ESP3Frame::read_from(&mut &TELEGRAM[..]).unwrap();
+        command_sender.send(OutgoingCommand::from(frame)).unwrap();
+
+        assert!(run(&mut port, event_sender, command_receiver).is_err());
+        assert_eq!(port.written(), &TELEGRAM[..]);
+
+        // A corrupted raw packet is skipped
+        let mut port = MockPort::default();
+        let (event_sender, _event_receiver) = mpsc::channel();
+        let (command_sender, command_receiver) = mpsc::channel();
+        let mut corrupted = TELEGRAM.to_vec();
+        corrupted[10] ^= 0xff;
+        command_sender.send(OutgoingCommand::Raw(corrupted)).unwrap();
+        command_sender.send(OutgoingCommand::from(TELEGRAM.to_vec())).unwrap();
+
+        assert!(run(&mut port, event_sender, command_receiver).is_err());
+        assert_eq!(port.written(), &TELEGRAM[..]);
+    }
+
+    #[test]
+    fn given_dropped_event_receiver_then_stop() {
+        let mut port = MockPort::new([TELEGRAM]);
+        let (event_sender, event_receiver) = mpsc::channel();
+        let (_command_sender, command_receiver) = mpsc::channel();
+        drop(event_receiver);
+
+        assert!(matches!(run(&mut port, event_sender, command_receiver), Err(CommunicatorError::ChannelClosed)));
+    }
+
+    #[test]
+    fn given_dropped_command_sender_then_stop_cleanly() {
+        let mut port = MockPort::new([TELEGRAM]);
+        let (event_sender, _event_receiver) = mpsc::channel();
+        let (command_sender, command_receiver) = mpsc::channel::<OutgoingCommand>();
+        drop(command_sender);
+
+        assert!(run(&mut port, event_sender, command_receiver).is_ok());
+    }
+
+    #[test]
+    fn given_read_error_then_reopen_the_port_and_go_on() {
+        let (event_sender, event_receiver) = mpsc::channel();
+        let (_command_sender, command_receiver) = mpsc::channel();
+        let policy = ReconnectPolicy { attempts: 2, backoff: Duration::from_millis(1) };
+
+        // The first port fails after a telegram, the reopened one works, then the port can't be reopened anymore
+        let mut reopened = 0;
+        let reopen = || {
+            reopened += 1;
+            match reopened {
+                1 => Ok(MockPort::new([TELEGRAM])),
+                _ => Err(CommunicatorError::PortDisconnected),
+            }
+        };
+        let result = run_reconnecting(MockPort::new([TELEGRAM]), reopen, event_sender, command_receiver, policy);
+
+        assert!(matches!(result, Err(CommunicatorError::PortDisconnected)));
+        assert_eq!(event_receiver.try_iter().count(), 2);
+        // 1 successful reopening, then 2 failed attempts
+        assert_eq!(reopened, 3);
+    }
+
+    #[test]
+    fn given_exhausted_port_then_report_disconnection() {
+        let mut port = MockPort::new([TELEGRAM]);
+        let (event_sender, _event_receiver) = mpsc::channel();
+        let (_command_sender, command_receiver) = mpsc::channel();
+
+        assert!(matches!(run(&mut port, event_sender, command_receiver), Err(CommunicatorError::PortDisconnected)));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn given_async_duplex_then_emit_telegrams_and_write_commands() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let (port, mut device) = tokio::io::duplex(64);
+        let (event_sender, mut event_receiver) = tokio::sync::mpsc::channel(4);
+        let (command_sender, command_receiver) = tokio::sync::mpsc::channel(4);
+        let communicator = tokio::spawn(run_async_on(port, event_sender, command_receiver));
+
+        // Telegram received in two parts
+        device.write_all(&TELEGRAM[..10]).await.unwrap();
+        device.write_all(&TELEGRAM[10..]).await.unwrap();
+        let esp3_packet = event_receiver.recv().await.unwrap();
+        assert_eq!(Vec::from(&esp3_packet), TELEGRAM.to_vec());
+
+        command_sender.send(OutgoingCommand::Raw(TELEGRAM.to_vec())).await.unwrap();
+        let mut written = [0; TELEGRAM.len()];
+        device.read_exact(&mut written).await.unwrap();
+        assert_eq!(written, TELEGRAM);
+
+        // Dropping the command sender stops the loop
+        drop(command_sender);
+        assert!(communicator.await.unwrap().is_ok());
+    }
+}
@@ -27,6 +27,16 @@
 //! assert_eq!(crc, 0x00);
 //! ```
 //! 
+//! Or feed it incrementally, eg. as bytes arrive from the serial port:
+//!
+//! ```
+//! # use enocean::crc8::*;
+//! let mut crc = CRC8::new();
+//! crc.update(b"Hello, ");
+//! crc.update(b"World!");
+//! assert_eq!(crc.finalize(), 0x87);
+//! ```
+//! 
 //! You can easily throw an error on CRC failure:
 //! 
 //! ```
@@ -83,10 +93,25 @@ impl From<&[u8]> for CRC8 {
 }
 
 impl CRC8 {
-    pub fn extend(mut self, bytes: &[u8]) -> Self {
+    /// A CRC over no bytes yet
+    pub fn new() -> Self {
+        CRC8::default()
+    }
+
+    /// Feed more bytes to the CRC
+    pub fn update(&mut self, bytes: &[u8]) {
         for byte in bytes {
             self.state = CRC_TABLE[(self.state ^ byte) as usize]
         }
+    }
+
+    /// The CRC of every byte fed so far
+    pub fn finalize(self) -> u8 {
+        self.state
+    }
+
+    pub fn extend(mut self, bytes: &[u8]) -> Self {
+        self.update(bytes);
         self
     }
 
@@ -99,4 +124,37 @@ impl CRC8 {
 /// Allow to check the integrity of a message
 pub fn compute_crc8(msg: &[u8]) -> u8 {
     CRC8::from(msg).into()
-}
\ No newline at end of file
+}
+
+/// Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn given_sample_telegrams_then_incremental_and_one_shot_crc_match() {
+        let telegrams: [&[u8]; 2] = [
+            // F60201 when pushed
+            &[85, 0, 7, 7, 1, 122, 246, 112, 254, 245, 143, 245, 48, 1, 255, 255, 255, 255, 46, 0, 249],
+            // A50401 when button is not pushed
+            &[85, 0, 10, 7, 1, 235, 165, 0, 229, 204, 10, 5, 17, 114, 247, 0, 1, 255, 255, 255, 255, 54, 0, 213],
+        ];
+        for telegram in telegrams {
+            let (header, rest) = telegram[1..].split_at(4);
+            let data = &rest[1..rest.len() - 1];
+
+            let mut crc = CRC8::new();
+            crc.update(&header[..2]);
+            crc.update(&header[2..]);
+            assert_eq!(crc.finalize(), compute_crc8(header));
+            assert_eq!(compute_crc8(header), rest[0]);
+
+            let mut crc = CRC8::new();
+            for chunk in data.chunks(3) {
+                crc.update(chunk);
+            }
+            assert_eq!(crc.finalize(), compute_crc8(data));
+            assert_eq!(compute_crc8(data), rest[rest.len() - 1]);
+        }
+    }
+}
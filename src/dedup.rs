@@ -0,0 +1,71 @@
+//! Filter of repeated radio telegrams (eg. sent several times by a rocker switch, or forwarded by repeaters)
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::enocean::{DataType, ESP3};
+use crate::packet::Address;
+
+/// Remembers the ERP1/ERP2 telegrams seen during the last `window`, keyed on their sender and payload.
+///
+/// A telegram is new if no identical one was seen in the `window` before it.
+/// Other packets (responses, events..) are always new.
+#[derive(Debug, Clone)]
+pub struct Deduplicator {
+    window: Duration,
+    seen: HashMap<(Address, Vec<u8>), Instant>,
+}
+
+impl Deduplicator {
+    /// A deduplicator filtering telegrams repeated within `window`
+    pub fn new(window: Duration) -> Self {
+        Deduplicator { window, seen: HashMap::new() }
+    }
+
+    /// Whether `esp` is new, received now
+    pub fn is_new(&mut self, esp: &ESP3) -> bool {
+        self.is_new_at(esp, Instant::now())
+    }
+
+    /// Whether `esp` is new, received at `now`
+    pub fn is_new_at(&mut self, esp: &ESP3, now: Instant) -> bool {
+        let key = match &esp.data {
+            DataType::Erp1Data { sender_id, payload, .. } | DataType::Erp2Data { sender_id, payload, .. } => {
+                (*sender_id, payload.clone())
+            }
+            _ => return true,
+        };
+        // Forget the telegrams which are out of the window
+        let window = self.window;
+        self.seen.retain(|_, seen_at| now.saturating_duration_since(*seen_at) < window);
+
+        if self.seen.contains_key(&key) {
+            return false;
+        }
+        self.seen.insert(key, now);
+        true
+    }
+}
+
+/// Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enocean::esp3_of_enocean_message;
+
+    // Valid message from a necklace pushbutton (EEP F6-01-01)
+    const TELEGRAM: [u8; 21] = [
+        85, 0, 7, 7, 1, 122, 246, 0, 254, 245, 143, 212, 32, 2, 255, 255, 255, 255, 48, 0, 39,
+    ];
+
+    #[test]
+    fn given_identical_telegrams_then_filter_them_within_the_window() {
+        let telegram = esp3_of_enocean_message(&TELEGRAM).unwrap();
+        let mut deduplicator = Deduplicator::new(Duration::from_millis(100));
+        let start = Instant::now();
+
+        assert!(deduplicator.is_new_at(&telegram, start));
+        assert!(!deduplicator.is_new_at(&telegram, start + Duration::from_millis(5)));
+        assert!(deduplicator.is_new_at(&telegram, start + Duration::from_millis(150)));
+    }
+}
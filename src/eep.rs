@@ -1,296 +1,1365 @@
 //! EnOcean Equipment Profiles - interpretation of radio packet payloads
 
 use crate::enocean::*;
+use crate::frame::ESP3Frame;
+use crate::packet::{Address, Status};
+use crate::util::{bit_of_byte, bits_of_byte};
 use crate::*;
-use std::collections::HashMap;
+use std::borrow::Borrow;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+use thiserror::Error;
 
+/// Parse the payload of an ERP1 packet according to the EEP of its sender, looked up in the default registry.
+/// Same as [`parse_erp1_typed`], with the result converted to a map of EEP field shortcuts to values.
+/// A payload too short for the EEP gives an "Error" entry instead of an error.
 pub fn parse_erp1_payload(esp: &ESP3) -> ParseEspResult<HashMap<String, String>> {
-    //
-    match &esp.data {
+    parse_erp1_payload_with(esp, default_registry())
+}
+
+/// Same as [`parse_erp1_payload`], with the EEP of the sender looked up in `registry`.
+pub fn parse_erp1_payload_with(esp: &ESP3, registry: &DeviceRegistry) -> ParseEspResult<HashMap<String, String>> {
+    payload_map(parse_erp1_typed(esp, registry))
+}
+
+/// Same as [`parse_erp1_payload_with`], but telegrams of senders with an unknown EEP are returned raw,
+/// see [`parse_erp1_typed_with`].
+pub fn parse_erp1_payload_lenient(esp: &ESP3, registry: &DeviceRegistry) -> ParseEspResult<HashMap<String, String>> {
+    payload_map(parse_erp1_typed_with(esp, registry, false))
+}
+
+fn payload_map(parsed: ParseEspResult<ParsedTelegram>) -> ParseEspResult<HashMap<String, String>> {
+    match parsed {
+        Ok(parsed) => Ok(HashMap::from(&parsed)),
+        // Payloads too short for their EEP are reported as an "Error" entry, as before the typed API
+        Err(e) if e.kind == ParseEspErrorKind::IncompleteMessage => {
+            Ok(HashMap::from([(String::from("Error"), e.message)]))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Parse the payload of an ERP1 (or ERP2) packet according to the EEP of its sender, looked up in `registry`.
+pub fn parse_erp1_typed(esp: &ESP3, registry: &DeviceRegistry) -> ParseEspResult<ParsedTelegram> {
+    parse_erp1_typed_with(esp, registry, true)
+}
+
+/// Same as [`parse_erp1_typed`], but telegrams whose sender is not in `allowed_senders` are rejected
+/// with an `UnknownSender` error before their payload is parsed.
+pub fn parse_erp1_typed_filtered(
+    esp: &ESP3,
+    registry: &DeviceRegistry,
+    allowed_senders: &HashSet<Address>,
+) -> ParseEspResult<ParsedTelegram> {
+    let (sender_id, byte_index) = match &esp.data {
+        // The sender ID is followed by the status byte in ERP1 data
+        DataType::Erp1Data { sender_id, .. } => (sender_id, Some(esp.data_length() as i16 + 1)),
+        DataType::Erp2Data { sender_id, .. } => (sender_id, None),
+        _ => return parse_erp1_typed(esp, registry),
+    };
+    if !allowed_senders.contains(sender_id) {
+        return Err(ParseEspError {
+            message: format!("Telegram from {}, which is not an allowed sender", sender_id),
+            packet: Vec::from(esp),
+            byte_index,
+            kind: ParseEspErrorKind::UnknownSender,
+        });
+    }
+    parse_erp1_typed(esp, registry)
+}
+
+/// Same as [`parse_erp1_typed`], but unless `strict`, a telegram of a known RORG whose sender has an unknown EEP
+/// is returned as [`ParsedTelegram::Raw`] instead of an "Unknown EEP" error.
+pub fn parse_erp1_typed_with(esp: &ESP3, registry: &DeviceRegistry, strict: bool) -> ParseEspResult<ParsedTelegram> {
+    let (rorg, sender_id, status, payload) = match &esp.data {
         // ERP Treatments
         DataType::Erp1Data {
-            rorg: _rorg,
+            rorg,
             sender_id,
-            status: _status,
+            status,
             payload,
-        } => {
-            match get_eep(sender_id) {
-                // The way we parse the packet payload depends on its EEP
-                Some(EEP::A50401) => Ok(parse_a50401_data(&payload)),
-                Some(EEP::F60201) => Ok(parse_f60201_data(&payload)),
-                Some(EEP::F60202) => Ok(parse_f60202_data(&payload)),
-                Some(EEP::D2010E) => Ok(parse_d201_data(&payload)),
-                Some(EEP::D50001) => Ok(parse_d50001_data(&payload)),
-
-                _ => {
-                    return Err(ParseEspError {
-                        message: String::from("Unknown EEP"),
-                        byte_index: None,
-                        packet: Vec::from(esp),
-                        kind: ParseEspErrorKind::Unimplemented,
-                    })
-                }
-            }
+        } => (rorg, sender_id, Some(*status), payload),
+        DataType::Erp2Data {
+            rorg,
+            sender_id,
+            payload,
+            ..
+        } => (rorg, sender_id, None, payload),
+        _ => {
+            return Err(ParseEspError {
+                message: String::from("Unknown or Unimplemented yet packet type"),
+                packet: Vec::from(esp),
+                byte_index: Some(6),
+                kind: ParseEspErrorKind::Unimplemented,
+            })
         }
+    };
+    // UTE teach-in queries do not depend on the EEP of the sender (which may not be known yet)
+    if *rorg == Rorg::Ute {
+        return Ok(ParsedTelegram::UteTeachIn(UteTeachIn::decode(payload)?));
+    }
+    // Neither do Smart Ack telegrams
+    if matches!(rorg, Rorg::SmLrnReq | Rorg::SmLrnAns | Rorg::SmRec) {
+        return Ok(ParsedTelegram::SmartAck(SmartAckTelegram::decode(*rorg, payload, *sender_id)?));
+    }
+    match registry.lookup(sender_id.as_bytes()) {
+        // The way we parse the packet payload depends on its EEP
+        Some(EEP::A50205) => Ok(ParsedTelegram::A50205(A50205Reading::decode(payload)?)),
+        Some(EEP::A50401) => Ok(ParsedTelegram::A50401(A50401Reading::decode(payload)?)),
+        Some(EEP::A50402) => Ok(ParsedTelegram::A50402(A50401Reading::decode_a50402(payload)?)),
+        Some(EEP::A50403) => Ok(ParsedTelegram::A50403(A50401Reading::decode_a50403(payload)?)),
+        Some(EEP::A50701) => Ok(ParsedTelegram::A50701(A50701Reading::decode(payload)?)),
+        Some(EEP::A50801) => Ok(ParsedTelegram::A50801(A50801Reading::decode(payload)?)),
+        Some(EEP::A51201) => Ok(ParsedTelegram::A51201(A51201Reading::decode(payload)?)),
+        Some(EEP::A52001) => Ok(ParsedTelegram::A52001(A52001Reading::decode(payload)?)),
+        Some(EEP::F60201) => Ok(ParsedTelegram::F60201(F60201Reading::decode(payload)?)),
+        Some(EEP::F60202) => Ok(ParsedTelegram::F60202(F60202Reading::decode(payload)?)),
+        Some(EEP::F61000) => Ok(ParsedTelegram::F61000(F61000Reading::decode(payload)?)),
+        Some(EEP::D2010E) => Ok(ParsedTelegram::D2010E(D201Reading::decode(payload)?)),
+        Some(EEP::D50001) => Ok(ParsedTelegram::D50001(D50001Reading::decode(payload)?)),
+
+        None if !strict && *rorg != Rorg::Undefined => Ok(ParsedTelegram::Raw(RawTelegram {
+            rorg: *rorg,
+            sender_id: *sender_id,
+            status,
+            payload: payload.clone(),
+        })),
         _ => Err(ParseEspError {
-            message: String::from("Unknown or Unimplemented yet packet type"),
+            message: String::from("Unknown EEP"),
+            byte_index: None,
             packet: Vec::from(esp),
-            byte_index: Some(6),
             kind: ParseEspErrorKind::Unimplemented,
         }),
     }
 }
+/// Whether an ERP1 (or ERP2) telegram is a teach-in rather than a data telegram, whatever the EEP of its sender :
+/// LRN bit (DB0 bit 3, 0 = teach-in) for 4BS and 1BS telegrams, UTE telegrams are always teach-in.
+/// Other radio organizations (eg. RPS switches) have no learn bit, and are never considered as teach-in.
+pub fn is_teach_in(esp: &ESP3) -> bool {
+    let (rorg, payload) = match &esp.data {
+        DataType::Erp1Data { rorg, payload, .. } | DataType::Erp2Data { rorg, payload, .. } => (rorg, payload),
+        _ => return false,
+    };
+    match (rorg, payload.as_slice()) {
+        (Rorg::Bs4, [_, _, _, db0]) | (Rorg::Bs1, [db0]) => !bit_of_byte(3, db0),
+        (Rorg::Ute, _) => true,
+        _ => false,
+    }
+}
+
+/// EEP and manufacturer ID advertised by a 4BS teach-in telegram with the LRN type bit (DB0 bit 7) set :
+/// FUNC (DB3 bits 7-2), TYPE (DB3 bits 1-0 and DB2 bits 7-3) and manufacturer ID (DB2 bits 2-0 and DB1).
+/// `None` for data telegrams, and teach-in telegrams without EEP.
+pub fn parse_4bs_teach_in(payload: &[u8]) -> Option<(EepId, u16)> {
+    let [db3, db2, db1, db0] = *payload else { return None };
+    if bit_of_byte(3, &db0) || !bit_of_byte(7, &db0) {
+        return None;
+    }
+    let func = db3 >> 2;
+    let typ = (db3 & 0b11) << 5 | db2 >> 3;
+    let manufacturer_id = ((db2 & 0b111) as u16) << 8 | db1 as u16;
+    Some((EepId { rorg: 0xa5, func, typ }, manufacturer_id))
+}
+
+/// RORG-FUNC-TYPE code of an EnOcean Equipment Profile, written `A5-04-01`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EepId {
+    pub rorg: u8,
+    pub func: u8,
+    pub typ: u8,
+}
+
+impl fmt::Display for EepId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:02X}-{:02X}-{:02X}", self.rorg, self.func, self.typ)
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("Invalid EEP, expected 3 hex bytes as RR-FF-TT")]
+pub struct ParseEepIdError;
+
+impl FromStr for EepId {
+    type Err = ParseEepIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut code = [0u8; 3];
+        let mut bytes = s.split('-');
+        for byte in code.iter_mut() {
+            let hex = bytes.next()
+                .filter(|hex| hex.len() == 2 && hex.bytes().all(|c| c.is_ascii_hexdigit()))
+                .ok_or(ParseEepIdError)?;
+            *byte = u8::from_str_radix(hex, 16).map_err(|_| ParseEepIdError)?;
+        }
+        match bytes.next() {
+            None => Ok(EepId { rorg: code[0], func: code[1], typ: code[2] }),
+            Some(_) => Err(ParseEepIdError),
+        }
+    }
+}
+
 /// These EEP are currently supported by this lib
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum EEP {
+    A50205,
     A50401,
+    A50402,
+    A50403,
+    A50701,
+    A50801,
+    A51201,
+    A52001,
     D2010E, //partially supported
     D50001,
     F60201,
     F60202,
+    F61000,
+}
+
+/// RORG-FUNC-TYPE code of every supported EEP
+const SUPPORTED_EEPS: &[(EEP, EepId)] = &[
+    (EEP::A50205, EepId { rorg: 0xa5, func: 0x02, typ: 0x05 }),
+    (EEP::A50401, EepId { rorg: 0xa5, func: 0x04, typ: 0x01 }),
+    (EEP::A50402, EepId { rorg: 0xa5, func: 0x04, typ: 0x02 }),
+    (EEP::A50403, EepId { rorg: 0xa5, func: 0x04, typ: 0x03 }),
+    (EEP::A50701, EepId { rorg: 0xa5, func: 0x07, typ: 0x01 }),
+    (EEP::A50801, EepId { rorg: 0xa5, func: 0x08, typ: 0x01 }),
+    (EEP::A51201, EepId { rorg: 0xa5, func: 0x12, typ: 0x01 }),
+    (EEP::A52001, EepId { rorg: 0xa5, func: 0x20, typ: 0x01 }),
+    (EEP::D2010E, EepId { rorg: 0xd2, func: 0x01, typ: 0x0e }),
+    (EEP::D50001, EepId { rorg: 0xd5, func: 0x00, typ: 0x01 }),
+    (EEP::F60201, EepId { rorg: 0xf6, func: 0x02, typ: 0x01 }),
+    (EEP::F60202, EepId { rorg: 0xf6, func: 0x02, typ: 0x02 }),
+    (EEP::F61000, EepId { rorg: 0xf6, func: 0x10, typ: 0x00 }),
+];
+
+impl EEP {
+    /// Get a supported EEP from its RORG-FUNC-TYPE code (eg. as found in a teach-in query)
+    pub fn from_profile(rorg: u8, func: u8, eep_type: u8) -> Option<EEP> {
+        EEP::from_id(EepId { rorg, func, typ: eep_type })
+    }
+
+    /// Get a supported EEP from its code, see [`EEP::from_profile`]
+    pub fn from_id(id: EepId) -> Option<EEP> {
+        SUPPORTED_EEPS.iter().find(|(_, eep_id)| *eep_id == id).map(|(eep, _)| *eep)
+    }
+
+    /// RORG-FUNC-TYPE code of this EEP
+    pub fn id(&self) -> EepId {
+        SUPPORTED_EEPS.iter()
+            .find(|(eep, _)| eep == self)
+            .map(|(_, id)| *id)
+            .expect("Every EEP has its code in SUPPORTED_EEPS")
+    }
 }
 
 /// These D201 (eg. smart plugs) commands are supported by this lib
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum D201CommandList {
     On,
     Off,
     QueryEnergy,
     QueryPower,
     DefaultConfig,
+    /// CMD 0x3 : Actuator Status Query, for an I/O channel (0x1E : all channels).
+    /// The plug answers with a CMD 0x4 Actuator Status Response, parsed into `PF`, `PFD`, `I/O` and `OV` fields.
+    QueryStatus { channel: u8 },
+    /// CMD 0x1 : Actuator Set Output, for an I/O channel (0x1E : all channels).
+    /// `percent` goes from 0 (OFF) to 100. `dim_timer` (1..=3) selects one of the ramp timers set in the plug config,
+    /// the output is switched immediately if `None`.
+    SetDimValue { channel: u8, percent: u8, dim_timer: Option<u8> },
 }
 /// These F602 (eg. PTM) messages emulation are supported by this lib
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum F602EmulateCommand {
     MoveBlindClosed,
     MoveBlindOpen
 }
 
-/// Link between EnOcean ID and EEP. This part has to be improved (stock EEP<->ID somehow)...
-pub fn get_eep(id: &[u8; 4]) -> Option<EEP> {
-    match id {
-        [5, 17, 114, 247] => Some(EEP::A50401),
-        [254, 245, 143, 245] => Some(EEP::F60201),
-        [0xFE, 0xF7, 0x91, 0x7C] => Some(EEP::F60201),
-        [0, 49, 192, 249] => Some(EEP::F60202),
-        [0x05, 0x0a, 0x3d, 0x6a] => Some(EEP::D2010E),
-        [0x01, 0x92, 0x3d, 0xa8] => Some(EEP::D50001),
+/// Link between EnOcean ID and EEP, filled at runtime with the devices you know about.
+/// The default registry contains a few devices used for the development of this lib.
+#[derive(Debug, Clone)]
+pub struct DeviceRegistry {
+    devices: HashMap<[u8; 4], EEP>,
+}
 
-        _ => None,
+impl DeviceRegistry {
+    /// An empty registry
+    pub fn new() -> Self {
+        DeviceRegistry { devices: HashMap::new() }
+    }
+
+    /// Register (or replace) the EEP of a device. Returns the previously registered EEP, if any.
+    pub fn register(&mut self, id: [u8; 4], eep: EEP) -> Option<EEP> {
+        self.devices.insert(id, eep)
+    }
+
+    /// Get the EEP of a device
+    pub fn lookup(&self, id: &[u8; 4]) -> Option<&EEP> {
+        self.devices.get(id)
     }
-}
 
-/// Util : get tha value of a specific bit in a byte
-fn bit_of_byte(bit_nb: u8, byte: &u8) -> bool {
-    ((byte >> bit_nb) & 1) != 0
+    /// Register the sender of a 4BS teach-in telegram advertising its EEP (see [`parse_4bs_teach_in`]),
+    /// if this EEP is supported. Returns the registered EEP.
+    pub fn register_teach_in(&mut self, esp: &ESP3) -> Option<EEP> {
+        let (sender_id, payload) = match &esp.data {
+            DataType::Erp1Data { rorg: Rorg::Bs4, sender_id, payload, .. }
+            | DataType::Erp2Data { rorg: Rorg::Bs4, sender_id, payload, .. } => (sender_id, payload),
+            _ => return None,
+        };
+        let (eep_id, _manufacturer_id) = parse_4bs_teach_in(payload)?;
+        let eep = EEP::from_id(eep_id)?;
+        self.register(*sender_id.as_bytes(), eep);
+        Some(eep)
+    }
 }
-/// Util : Byte to array of 8 bits conversion
-fn bits_of_byte(byte: u8) -> [bool; 8] {
-    let mut value: [bool; 8] = [false; 8];
-    for i in 0..8 {
-        value[7 - i] = bit_of_byte(i as u8, &byte);
+
+impl Default for DeviceRegistry {
+    fn default() -> Self {
+        let mut registry = DeviceRegistry::new();
+        registry.register([5, 17, 114, 247], EEP::A50401);
+        registry.register([254, 245, 143, 245], EEP::F60201);
+        registry.register([0xFE, 0xF7, 0x91, 0x7C], EEP::F60201);
+        registry.register([0, 49, 192, 249], EEP::F60202);
+        registry.register([0x05, 0x0a, 0x3d, 0x6a], EEP::D2010E);
+        registry.register([0x01, 0x92, 0x3d, 0xa8], EEP::D50001);
+        registry
     }
-    value
 }
+
+/// The default registry, built once, see [`DeviceRegistry::default`]
+pub fn default_registry() -> &'static DeviceRegistry {
+    static DEFAULT_REGISTRY: OnceLock<DeviceRegistry> = OnceLock::new();
+    DEFAULT_REGISTRY.get_or_init(DeviceRegistry::default)
+}
+
+/// Link between EnOcean ID and EEP, using the default registry.
+pub fn get_eep(id: &[u8; 4]) -> Option<EEP> {
+    default_registry().lookup(id).copied()
+}
+
 // ---------------------------------------------------------------------//
 // ---------------- Enocean Message parsing ----------------------------//
 // ---------------------------------------------------------------------//
-/// Specific parsing function for Temperature and humidity sensor
-fn parse_a50401_data(payload: &Vec<u8>) -> HashMap<String, String> {
-    let mut parsed = HashMap::new();
-    parsed.insert(String::from("HUM"), format!("{}", payload[1] as f32 * 0.4));
-    parsed.insert(
-        String::from("TMP"),
-        format!("{}", payload[2] as f32 * (40 as f32) / (250 as f32)),
-    );
-    match bit_of_byte(3, &payload[3]) {
-        false => parsed.insert(String::from("LRNB"), String::from("Teach-in telegram")),
-        true => parsed.insert(String::from("LRNB"), String::from("Data telegram")),
-    };
-    match bit_of_byte(1, &payload[3]) {
-        false => parsed.insert(
-            String::from("TSN"),
-            String::from("Temperature sensor not available"),
-        ),
-        true => parsed.insert(
-            String::from("TSN"),
-            String::from("Temperature sensor available"),
-        ),
-    };
-    parsed
+/// Content of an ERP1 telegram, depending on the EEP of its sender
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParsedTelegram {
+    A50205(A50205Reading),
+    A50401(A50401Reading),
+    A50402(A50401Reading),
+    A50403(A50401Reading),
+    A50701(A50701Reading),
+    A50801(A50801Reading),
+    A51201(A51201Reading),
+    A52001(A52001Reading),
+    D2010E(D201Reading),
+    D50001(D50001Reading),
+    F60201(F60201Reading),
+    F60202(F60202Reading),
+    F61000(F61000Reading),
+    UteTeachIn(UteTeachIn),
+    SmartAck(SmartAckTelegram),
+    /// Telegram of a sender whose EEP is unknown, see [`parse_erp1_typed_with`]
+    Raw(RawTelegram),
+}
+
+impl From<&ParsedTelegram> for HashMap<String, String> {
+    fn from(parsed: &ParsedTelegram) -> Self {
+        match parsed {
+            ParsedTelegram::A50205(reading) => HashMap::from(reading),
+            ParsedTelegram::A50401(reading)
+            | ParsedTelegram::A50402(reading)
+            | ParsedTelegram::A50403(reading) => HashMap::from(reading),
+            ParsedTelegram::A50701(reading) => HashMap::from(reading),
+            ParsedTelegram::A50801(reading) => HashMap::from(reading),
+            ParsedTelegram::A51201(reading) => HashMap::from(reading),
+            ParsedTelegram::A52001(reading) => HashMap::from(reading),
+            ParsedTelegram::D2010E(reading) => HashMap::from(reading),
+            ParsedTelegram::D50001(reading) => HashMap::from(reading),
+            ParsedTelegram::F60201(reading) => HashMap::from(reading),
+            ParsedTelegram::F60202(reading) => HashMap::from(reading),
+            ParsedTelegram::F61000(reading) => HashMap::from(reading),
+            ParsedTelegram::UteTeachIn(query) => HashMap::from(query),
+            ParsedTelegram::SmartAck(telegram) => HashMap::from(telegram),
+            ParsedTelegram::Raw(telegram) => HashMap::from(telegram),
+        }
+    }
 }
-fn parse_d50001_data(payload: &Vec<u8>) -> HashMap<String, String> {
-    let mut parsed = HashMap::new();
-    match bit_of_byte(4, &payload[0]) {
-        false => parsed.insert(String::from("LRNB"), String::from("pressed")),
-        true => parsed.insert(String::from("LRNB"), String::from("not pressed")),
-    };
-    match bit_of_byte(7, &payload[0]) {
-        false => parsed.insert(String::from("CO"), String::from("open")),
-        true => parsed.insert(String::from("CO"), String::from("closed")),
-    };
-    parsed
-}
-/// Specific parsing function for pushbutton
-fn parse_f60201_data(payload: &Vec<u8>) -> HashMap<String, String> {
-    let mut result = HashMap::new();
-    match bit_of_byte(3, &payload[0]) {
-        false => result.insert(String::from("LRNB"), String::from("Teach-in telegram")),
-        true => result.insert(String::from("LRNB"), String::from("Data telegram")),
-    };
-    match payload[0] {
-        0x70 => result.insert(String::from("BTN"), String::from("Pressed")),
-        0x00 => result.insert(String::from("BTN"), String::from("Released")),
-        _ => result.insert(String::from("BTN"), String::from("Unknown")), //todo : Erreur
-    };
-    result
-}
-/// Specific parsing function for soft remote
-fn parse_f60202_data(payload: &Vec<u8>) -> HashMap<String, String> {
-    let mut result = HashMap::new();
-    let payload_bits = bits_of_byte(payload[0]);
-    match payload_bits[0..3] {
-        [false, false, false] => result.insert(String::from("R1"), String::from("A1")),
-        [false, false, true] => result.insert(String::from("R1"), String::from("A0")),
-        [false, true, false] => result.insert(String::from("R1"), String::from("B1")),
-        [false, true, true] => result.insert(String::from("R1"), String::from("B0")),
-        _ => result.insert(String::from("R1"), String::from("Unknown")), //todo : Erreur
-    };
-    match payload_bits[3] {
-        false => result.insert(String::from("EB"), String::from("Released")),
-        true => result.insert(String::from("EB"), String::from("Pressed")),
-    };
-    match payload_bits[4..7] {
-        [false, false, false] => result.insert(String::from("R2"), String::from("A1")),
-        [false, false, true] => result.insert(String::from("R2"), String::from("A0")),
-        [false, true, false] => result.insert(String::from("R2"), String::from("B1")),
-        [false, true, true] => result.insert(String::from("R2"), String::from("B0")),
-        _ => result.insert(String::from("R1"), String::from("Unknown")), //todo : Erreur
-    };
-    match payload_bits[7] {
-        false => result.insert(String::from("SA"), String::from("No 2nd action")),
-        true => result.insert(String::from("SA"), String::from("2nd action valid")),
-    };
-    result
-}
-/// Specific parsing function for micro smart plug
-fn parse_d201_data(payload: &Vec<u8>) -> HashMap<String, String> {
-    // First we have to get CMD_ID:
-    let command_id: u8 = payload[0] & 0x0f;
-    let mut parsed = HashMap::new();
-
-    if command_id == 0x07 {
-        let db4_bits = bits_of_byte(payload[1]);
-        match db4_bits[0..3] {
-            [false, false, false] => parsed.insert(String::from("UN"), String::from("Energy [Ws]")),
-            [false, false, true] => parsed.insert(String::from("UN"), String::from("Energy [Wh]")),
-            [false, true, false] => parsed.insert(String::from("UN"), String::from("Energy [KWh]")),
-            [false, true, true] => parsed.insert(String::from("UN"), String::from("Power[W]")),
-            [true, false, false] => parsed.insert(String::from("UN"), String::from("Power[KW]")),
-            _ => parsed.insert(String::from("UN"), String::from("Error")), //todo : Erreur
-        };
 
-        parsed.insert(String::from("I/O"), format!("{}", payload[1] & 0b00011111));
+/// Util : check that an EEP payload holds at least `length` bytes
+fn check_payload_length(payload: &[u8], length: usize) -> ParseEspResult<()> {
+    if payload.len() < length {
+        return Err(ParseEspError {
+            kind: ParseEspErrorKind::IncompleteMessage,
+            message: String::from("Payload too short"),
+            byte_index: Some(payload.len() as i16),
+            packet: payload.to_vec(),
+        });
+    }
+    Ok(())
+}
 
-        // parsed.insert(String::from("MV"),format!("{}", payload[5] +payload[4]<< 8 +payload[3]<< 16 +payload[2]<< 24));
-        parsed.insert(
-            String::from("MV"),
-            format!(
-                "{}",
-                payload[5] as i32 + payload[4] as i32 * 256 + payload[3] as i32 * 65536
-            ),
-        );
-    } else if command_id == 0x04 {
-        let db2_bits = bits_of_byte(payload[0]);
-        match db2_bits[0] {
+/// Linear scaling of a raw field value into its physical range, as described in the EEP tables
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScaleRange {
+    pub raw_min: u8,
+    pub raw_max: u8,
+    pub scaled_min: f32,
+    pub scaled_max: f32,
+}
+
+impl ScaleRange {
+    /// Physical value of `raw`
+    pub fn apply(&self, raw: u8) -> f32 {
+        (raw as f32 - self.raw_min as f32) * (self.scaled_max - self.scaled_min)
+            / (self.raw_max as f32 - self.raw_min as f32)
+            + self.scaled_min
+    }
+}
+
+/// A5-04-01 humidity, 0..250 => 0..100 %
+const A5_04_HUMIDITY: ScaleRange = ScaleRange { raw_min: 0, raw_max: 250, scaled_min: 0.0, scaled_max: 100.0 };
+/// A5-04-01 temperature, 0..250 => 0..40 °C
+const A5_04_01_TEMPERATURE: ScaleRange = ScaleRange { raw_min: 0, raw_max: 250, scaled_min: 0.0, scaled_max: 40.0 };
+/// A5-04-02 temperature, 0..250 => -20..60 °C
+const A5_04_02_TEMPERATURE: ScaleRange = ScaleRange { raw_min: 0, raw_max: 250, scaled_min: -20.0, scaled_max: 60.0 };
+/// A5-04-03 humidity, 0..255 => 0..100 %
+const A5_04_03_HUMIDITY: ScaleRange = ScaleRange { raw_min: 0, raw_max: 255, scaled_min: 0.0, scaled_max: 100.0 };
+/// A5-20-01 temperature, 0..255 => 0..40 °C
+const A5_20_01_TEMPERATURE: ScaleRange = ScaleRange { raw_min: 0, raw_max: 255, scaled_min: 0.0, scaled_max: 40.0 };
+
+/// Util : LRNB field value
+fn learn_bit_description(learn: bool) -> String {
+    match learn {
+        true => String::from("Teach-in telegram"),
+        false => String::from("Data telegram"),
+    }
+}
+
+/// A5-02-05 : Temperature sensor (range 0°C to +40°C)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct A50205Reading {
+    /// Temperature [°C]
+    pub temperature: f32,
+    /// Teach-in telegram
+    pub learn: bool,
+}
+
+impl A50205Reading {
+    pub fn decode(payload: &[u8]) -> ParseEspResult<Self> {
+        check_payload_length(payload, 4)?;
+        Ok(A50205Reading {
+            // DB1 goes from 255 (0°C) down to 0 (+40°C)
+            temperature: 40.0 - payload[2] as f32 * 40.0 / 255.0,
+            learn: !bit_of_byte(3, &payload[3]),
+        })
+    }
+}
+
+impl From<&A50205Reading> for HashMap<String, String> {
+    fn from(reading: &A50205Reading) -> Self {
+        let mut parsed = HashMap::new();
+        parsed.insert(String::from("TMP"), format!("{}", reading.temperature));
+        parsed.insert(String::from("LRNB"), learn_bit_description(reading.learn));
+        parsed
+    }
+}
+
+/// A5-04-01 : Temperature and humidity sensor (range 0°C to +40°C), also used for A5-04-02 and A5-04-03
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct A50401Reading {
+    /// Temperature [°C]
+    pub temperature: f32,
+    /// Relative humidity [%]
+    pub humidity: f32,
+    /// Teach-in telegram
+    pub learn: bool,
+    pub temp_sensor_available: bool,
+}
+
+impl A50401Reading {
+    pub fn decode(payload: &[u8]) -> ParseEspResult<Self> {
+        check_payload_length(payload, 4)?;
+        Ok(A50401Reading {
+            temperature: A5_04_01_TEMPERATURE.apply(payload[2]),
+            humidity: A5_04_HUMIDITY.apply(payload[1]),
+            learn: !bit_of_byte(3, &payload[3]),
+            temp_sensor_available: bit_of_byte(1, &payload[3]),
+        })
+    }
+
+    /// A5-04-02 : Temperature and humidity sensor, range -20°C to +60°C
+    pub fn decode_a50402(payload: &[u8]) -> ParseEspResult<Self> {
+        check_payload_length(payload, 4)?;
+        Ok(A50401Reading {
+            temperature: A5_04_02_TEMPERATURE.apply(payload[2]),
+            humidity: A5_04_HUMIDITY.apply(payload[1]),
+            learn: !bit_of_byte(3, &payload[3]),
+            temp_sensor_available: bit_of_byte(1, &payload[3]),
+        })
+    }
+
+    /// A5-04-03 : Temperature and humidity sensor, range -20°C to +60°C, 10 bits temperature
+    pub fn decode_a50403(payload: &[u8]) -> ParseEspResult<Self> {
+        check_payload_length(payload, 4)?;
+        // DB3 : humidity 0..255, DB2 (bits 1..0) and DB1 : temperature 0..1023
+        let raw_temperature = u16::from_be_bytes([payload[1] & 0x03, payload[2]]);
+        Ok(A50401Reading {
+            temperature: -20.0 + raw_temperature as f32 * 80.0 / 1023.0,
+            humidity: A5_04_03_HUMIDITY.apply(payload[0]),
+            learn: !bit_of_byte(3, &payload[3]),
+            temp_sensor_available: true,
+        })
+    }
+}
+
+impl From<&A50401Reading> for HashMap<String, String> {
+    fn from(reading: &A50401Reading) -> Self {
+        let mut parsed = HashMap::new();
+        parsed.insert(String::from("HUM"), format!("{}", reading.humidity));
+        parsed.insert(String::from("TMP"), format!("{}", reading.temperature));
+        parsed.insert(String::from("LRNB"), learn_bit_description(reading.learn));
+        match reading.temp_sensor_available {
             false => parsed.insert(
-                String::from("PF"),
-                String::from("Power Failure Detection disabled/not supported"),
+                String::from("TSN"),
+                String::from("Temperature sensor not available"),
             ),
             true => parsed.insert(
-                String::from("PF"),
-                String::from("Power Failure Detection enabled"),
+                String::from("TSN"),
+                String::from("Temperature sensor available"),
             ),
         };
-        match db2_bits[1] {
-            false => parsed.insert(
-                String::from("PFD"),
-                String::from("Power Failure Detection disabled/not supported"),
+        parsed
+    }
+}
+
+/// A5-07-01 : Occupancy sensor (PIR)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct A50701Reading {
+    /// Supply voltage [V], if supported by the sensor
+    pub supply_voltage: Option<f32>,
+    pub motion_detected: bool,
+    /// Teach-in telegram
+    pub learn: bool,
+}
+
+impl A50701Reading {
+    pub fn decode(payload: &[u8]) -> ParseEspResult<Self> {
+        check_payload_length(payload, 4)?;
+        Ok(A50701Reading {
+            // DB3 : 0..250 for 0..5V
+            supply_voltage: bit_of_byte(0, &payload[3]).then(|| payload[0] as f32 * 5.0 / 250.0),
+            motion_detected: bit_of_byte(7, &payload[2]),
+            learn: !bit_of_byte(3, &payload[3]),
+        })
+    }
+}
+
+impl From<&A50701Reading> for HashMap<String, String> {
+    fn from(reading: &A50701Reading) -> Self {
+        let mut parsed = HashMap::new();
+        match reading.supply_voltage {
+            None => parsed.insert(
+                String::from("SVA"),
+                String::from("Supply voltage not supported"),
             ),
-            true => parsed.insert(String::from("PFD"), String::from("Power Failure Detected")),
+            Some(voltage) => {
+                parsed.insert(String::from("SVC"), format!("{}", voltage));
+                parsed.insert(String::from("SVA"), String::from("Supply voltage supported"))
+            }
         };
-        // ... insert here missing EEP fields
-        match payload[2] & 0b01111111 {
-            0x00 => parsed.insert(String::from("OV"), String::from("Output value : 0% or OFF")),
-            0x7F => parsed.insert(
-                String::from("OV"),
-                String::from("Output value : 1 to 100% or ON"),
-            ),
-            0x01..=0x64 => parsed.insert(String::from("OV"), String::from("Not used")),
-            0x65..=0x7E => parsed.insert(
-                String::from("OV"),
-                String::from("Output value not valid / not set"),
-            ),
-            _ => parsed.insert(String::from("OV"), String::from("Error")),
+        match reading.motion_detected {
+            false => parsed.insert(String::from("PIRS"), String::from("Uncertain of occupancy status")),
+            true => parsed.insert(String::from("PIRS"), String::from("Motion detected")),
         };
-    } else {
-        parsed.insert(String::from("Error"), String::from("Bad CMD ID"));
+        parsed.insert(String::from("LRNB"), learn_bit_description(reading.learn));
+        parsed
+    }
+}
+
+/// A5-08-01 : Light, temperature and occupancy sensor (range 0lx to 510lx, 0°C to +51°C)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct A50801Reading {
+    /// Supply voltage [V]
+    pub supply_voltage: f32,
+    /// Illumination [lx]
+    pub illumination: f32,
+    /// Temperature [°C]
+    pub temperature: f32,
+    pub motion_detected: bool,
+    pub occupancy_button_pressed: bool,
+    /// Teach-in telegram
+    pub learn: bool,
+}
+
+impl A50801Reading {
+    pub fn decode(payload: &[u8]) -> ParseEspResult<Self> {
+        check_payload_length(payload, 4)?;
+        // DB0 : LRN (bit 3), PIRS (bit 1, 0 = PIR on), OCC (bit 0, 0 = button pressed)
+        Ok(A50801Reading {
+            supply_voltage: payload[0] as f32 * 5.1 / 255.0,
+            illumination: payload[1] as f32 * 510.0 / 255.0,
+            temperature: payload[2] as f32 * 51.0 / 255.0,
+            motion_detected: !bit_of_byte(1, &payload[3]),
+            occupancy_button_pressed: !bit_of_byte(0, &payload[3]),
+            learn: !bit_of_byte(3, &payload[3]),
+        })
+    }
+}
+
+impl From<&A50801Reading> for HashMap<String, String> {
+    fn from(reading: &A50801Reading) -> Self {
+        let mut parsed = HashMap::new();
+        parsed.insert(String::from("SVC"), format!("{}", reading.supply_voltage));
+        parsed.insert(String::from("ILL"), format!("{}", reading.illumination));
+        parsed.insert(String::from("TMP"), format!("{}", reading.temperature));
+        match reading.motion_detected {
+            true => parsed.insert(String::from("PIR"), String::from("PIR on")),
+            false => parsed.insert(String::from("PIR"), String::from("PIR off")),
+        };
+        match reading.occupancy_button_pressed {
+            true => parsed.insert(String::from("OCC"), String::from("Button pressed")),
+            false => parsed.insert(String::from("OCC"), String::from("Button released")),
+        };
+        parsed.insert(String::from("LRNB"), learn_bit_description(reading.learn));
+        parsed
+    }
+}
+
+/// A5-12-01 : Automated meter reading, electricity
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct A51201Reading {
+    /// Meter reading, before scaling by `divisor`
+    pub meter_reading: u32,
+    /// Cumulative value [kWh] when true, current value [W] otherwise
+    pub cumulative: bool,
+    /// 1, 10, 100 or 1000
+    pub divisor: u16,
+    /// Tariff info, 0..15
+    pub tariff: u8,
+    /// Teach-in telegram
+    pub learn: bool,
+}
+
+impl A51201Reading {
+    pub fn decode(payload: &[u8]) -> ParseEspResult<Self> {
+        check_payload_length(payload, 4)?;
+        // DB3..DB1 : meter reading, DB0 : TI (bits 7..4), LRN (bit 3), DT (bit 2), DIV (bits 1..0)
+        Ok(A51201Reading {
+            meter_reading: u32::from_be_bytes([0, payload[0], payload[1], payload[2]]),
+            cumulative: !bit_of_byte(2, &payload[3]),
+            divisor: 10u16.pow((payload[3] & 0x03) as u32),
+            tariff: payload[3] >> 4,
+            learn: !bit_of_byte(3, &payload[3]),
+        })
+    }
+
+    /// Meter reading scaled by its divisor, in kWh (cumulative) or W (current)
+    pub fn value(&self) -> f64 {
+        self.meter_reading as f64 / self.divisor as f64
+    }
+}
+
+impl From<&A51201Reading> for HashMap<String, String> {
+    fn from(reading: &A51201Reading) -> Self {
+        let mut parsed = HashMap::new();
+        parsed.insert(String::from("MV"), format!("{}", reading.value()));
+        match reading.cumulative {
+            true => parsed.insert(String::from("DT"), String::from("Cumulative value [kWh]")),
+            false => parsed.insert(String::from("DT"), String::from("Current value [W]")),
+        };
+        parsed.insert(String::from("DIV"), format!("x/{}", reading.divisor));
+        parsed.insert(String::from("TI"), format!("{}", reading.tariff));
+        parsed.insert(String::from("LRNB"), learn_bit_description(reading.learn));
+        parsed
+    }
+}
+
+/// Generic 1BS (RORG 0xD5) telegram : a single data byte, whose bit 3 is the LRN bit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bs1Telegram {
+    pub data: u8,
+    /// Teach-in telegram (LRN bit = 0)
+    pub learn: bool,
+}
+
+/// Decode the payload of a 1BS telegram, whatever its EEP
+pub fn parse_bs1(payload: &[u8]) -> ParseEspResult<Bs1Telegram> {
+    check_payload_length(payload, 1)?;
+    Ok(Bs1Telegram { data: payload[0], learn: !bit_of_byte(3, &payload[0]) })
+}
+
+/// A5-20-01 : Battery powered actuator (eg. radiator valve), status sent by the actuator.
+///
+/// This EEP is bidirectional : the telegrams sent to the actuator (valve position or setpoint) have another layout,
+/// and are not decoded here.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct A52001Reading {
+    /// Current valve position [%]
+    pub current_value: u8,
+    pub service_on: bool,
+    pub energy_input_enabled: bool,
+    pub energy_storage_charged: bool,
+    /// The battery should be changed in the next days
+    pub battery_low: bool,
+    pub contact_cover_open: bool,
+    /// Temperature sensor failure, or temperature out of range
+    pub temperature_sensor_failure: bool,
+    pub window_open: bool,
+    pub actuator_obstructed: bool,
+    /// Temperature [°C]
+    pub temperature: f32,
+    /// Teach-in telegram
+    pub learn: bool,
+}
+
+impl A52001Reading {
+    pub fn decode(payload: &[u8]) -> ParseEspResult<Self> {
+        check_payload_length(payload, 4)?;
+        // DB2 : SO, ENIE, ES, BCAP (0 = change battery), CCO, FTS, DWO, ACO from bit 7 to bit 0
+        Ok(A52001Reading {
+            current_value: payload[0],
+            service_on: bit_of_byte(7, &payload[1]),
+            energy_input_enabled: bit_of_byte(6, &payload[1]),
+            energy_storage_charged: bit_of_byte(5, &payload[1]),
+            battery_low: !bit_of_byte(4, &payload[1]),
+            contact_cover_open: bit_of_byte(3, &payload[1]),
+            temperature_sensor_failure: bit_of_byte(2, &payload[1]),
+            window_open: bit_of_byte(1, &payload[1]),
+            actuator_obstructed: bit_of_byte(0, &payload[1]),
+            temperature: A5_20_01_TEMPERATURE.apply(payload[2]),
+            learn: !bit_of_byte(3, &payload[3]),
+        })
+    }
+}
+
+impl From<&A52001Reading> for HashMap<String, String> {
+    fn from(reading: &A52001Reading) -> Self {
+        let flag = |set: bool| String::from(if set { "true" } else { "false" });
+        let mut parsed = HashMap::new();
+        parsed.insert(String::from("CV"), format!("{}", reading.current_value));
+        parsed.insert(String::from("TMP"), format!("{}", reading.temperature));
+        parsed.insert(String::from("SO"), flag(reading.service_on));
+        parsed.insert(String::from("ENIE"), flag(reading.energy_input_enabled));
+        parsed.insert(String::from("ES"), flag(reading.energy_storage_charged));
+        match reading.battery_low {
+            true => parsed.insert(String::from("BCAP"), String::from("Change battery next days")),
+            false => parsed.insert(String::from("BCAP"), String::from("Battery OK")),
+        };
+        parsed.insert(String::from("CCO"), flag(reading.contact_cover_open));
+        parsed.insert(String::from("FTS"), flag(reading.temperature_sensor_failure));
+        parsed.insert(String::from("DWO"), flag(reading.window_open));
+        match reading.actuator_obstructed {
+            true => parsed.insert(String::from("ACTSTAT"), String::from("Actuator obstructed")),
+            false => parsed.insert(String::from("ACTSTAT"), String::from("Actuator OK")),
+        };
+        parsed.insert(String::from("LRNB"), learn_bit_description(reading.learn));
+        parsed
+    }
+}
+
+/// D5-00-01 : Single input contact
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct D50001Reading {
+    /// Teach-in telegram, sent when the learn button is pressed
+    pub learn_button_pressed: bool,
+    pub contact_closed: bool,
+}
+
+impl D50001Reading {
+    pub fn decode(payload: &[u8]) -> ParseEspResult<Self> {
+        let telegram = parse_bs1(payload)?;
+        // DB0 : LRN (bit 3), CO (bit 0, 1 = closed)
+        Ok(D50001Reading {
+            learn_button_pressed: telegram.learn,
+            contact_closed: bit_of_byte(0, &telegram.data),
+        })
+    }
+}
+
+impl From<&D50001Reading> for HashMap<String, String> {
+    fn from(reading: &D50001Reading) -> Self {
+        let mut parsed = HashMap::new();
+        match reading.learn_button_pressed {
+            true => parsed.insert(String::from("LRNB"), String::from("pressed")),
+            false => parsed.insert(String::from("LRNB"), String::from("not pressed")),
+        };
+        match reading.contact_closed {
+            false => parsed.insert(String::from("CO"), String::from("open")),
+            true => parsed.insert(String::from("CO"), String::from("closed")),
+        };
+        parsed
+    }
+}
+
+/// F6-02-01 : Light and blind control (rocker switch, pushbutton)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct F60201Reading {
+    /// Teach-in telegram
+    pub learn: bool,
+    /// 1st action, `None` when the energy bow is released
+    pub rocker_1: Option<RockerAction>,
+    pub energy_bow_pressed: bool,
+    /// 2nd action, if any
+    pub rocker_2: Option<RockerAction>,
+}
+
+impl F60201Reading {
+    pub fn decode(payload: &[u8]) -> ParseEspResult<Self> {
+        check_payload_length(payload, 1)?;
+        // DB0 : R1 (bits 7..5), EB (bit 4), R2 (bits 3..1), SA (bit 0)
+        let payload_bits = bits_of_byte(payload[0]);
+        let energy_bow_pressed = payload_bits[3];
+        let second_action_valid = energy_bow_pressed && payload_bits[7];
+        Ok(F60201Reading {
+            learn: !bit_of_byte(3, &payload[0]),
+            rocker_1: energy_bow_pressed
+                .then(|| RockerAction::from_bits(&payload_bits[0..3]))
+                .flatten(),
+            energy_bow_pressed,
+            rocker_2: second_action_valid
+                .then(|| RockerAction::from_bits(&payload_bits[4..7]))
+                .flatten(),
+        })
+    }
+}
+
+impl From<&F60201Reading> for HashMap<String, String> {
+    fn from(reading: &F60201Reading) -> Self {
+        let mut result = HashMap::new();
+        result.insert(String::from("LRNB"), learn_bit_description(reading.learn));
+        match reading.energy_bow_pressed {
+            true => {
+                result.insert(String::from("BTN"), String::from("Pressed"));
+                result.insert(String::from("EB"), String::from("Pressed"))
+            }
+            false => {
+                result.insert(String::from("BTN"), String::from("Released"));
+                result.insert(String::from("EB"), String::from("Released"))
+            }
+        };
+        match reading.rocker_1 {
+            Some(action) => result.insert(String::from("R1"), format!("{:?}", action)),
+            None => result.insert(String::from("R1"), String::from("None")),
+        };
+        match reading.rocker_2 {
+            Some(action) => {
+                result.insert(String::from("R2"), format!("{:?}", action));
+                result.insert(String::from("SA"), String::from("2nd action valid"))
+            }
+            None => result.insert(String::from("SA"), String::from("No 2nd action")),
+        };
+        result
+    }
+}
+
+/// Rocker action of a F6-02-xx switch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RockerAction {
+    A1,
+    A0,
+    B1,
+    B0,
+}
+
+impl RockerAction {
+    /// Decode a 3 bits rocker action, `None` if unknown
+    fn from_bits(bits: &[bool]) -> Option<Self> {
+        match bits {
+            [false, false, false] => Some(RockerAction::A1),
+            [false, false, true] => Some(RockerAction::A0),
+            [false, true, false] => Some(RockerAction::B1),
+            [false, true, true] => Some(RockerAction::B0),
+            _ => None,
+        }
+    }
+}
+
+/// F6-02-02 : Soft remote
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct F60202Reading {
+    /// 1st action, `None` if unknown
+    pub rocker_1: Option<RockerAction>,
+    pub energy_bow_pressed: bool,
+    /// 2nd action, `None` if unknown
+    pub rocker_2: Option<RockerAction>,
+    pub second_action_valid: bool,
+}
+
+impl F60202Reading {
+    pub fn decode(payload: &[u8]) -> ParseEspResult<Self> {
+        check_payload_length(payload, 1)?;
+        let payload_bits = bits_of_byte(payload[0]);
+        Ok(F60202Reading {
+            rocker_1: RockerAction::from_bits(&payload_bits[0..3]),
+            energy_bow_pressed: payload_bits[3],
+            rocker_2: RockerAction::from_bits(&payload_bits[4..7]),
+            second_action_valid: payload_bits[7],
+        })
+    }
+}
+
+impl From<&F60202Reading> for HashMap<String, String> {
+    fn from(reading: &F60202Reading) -> Self {
+        let mut result = HashMap::new();
+        let rocker_description = |rocker: Option<RockerAction>| match rocker {
+            Some(action) => format!("{:?}", action),
+            None => String::from("Unknown"),
+        };
+        result.insert(String::from("R1"), rocker_description(reading.rocker_1));
+        match reading.energy_bow_pressed {
+            false => result.insert(String::from("EB"), String::from("Released")),
+            true => result.insert(String::from("EB"), String::from("Pressed")),
+        };
+        result.insert(String::from("R2"), rocker_description(reading.rocker_2));
+        match reading.second_action_valid {
+            false => result.insert(String::from("SA"), String::from("No 2nd action")),
+            true => result.insert(String::from("SA"), String::from("2nd action valid")),
+        };
+        result
+    }
+}
+
+/// Position of a F6-10-00 window handle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandlePosition {
+    Closed,
+    Open,
+    Tilted,
+}
+
+/// F6-10-00 : Mechanical window handle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct F61000Reading {
+    /// `None` if unknown
+    pub handle: Option<HandlePosition>,
+}
+
+impl F61000Reading {
+    pub fn decode(payload: &[u8]) -> ParseEspResult<Self> {
+        check_payload_length(payload, 1)?;
+        // DB0 bits 7..4 : 11X0 open, 1111 closed, 1101 tilted
+        let handle = match payload[0] & 0xf0 {
+            0xf0 => Some(HandlePosition::Closed),
+            0xc0 | 0xe0 => Some(HandlePosition::Open),
+            0xd0 => Some(HandlePosition::Tilted),
+            _ => None,
+        };
+        Ok(F61000Reading { handle })
+    }
+}
+
+impl From<&F61000Reading> for HashMap<String, String> {
+    fn from(reading: &F61000Reading) -> Self {
+        let mut result = HashMap::new();
+        match reading.handle {
+            Some(position) => result.insert(String::from("HANDLE"), format!("{:?}", position)),
+            None => result.insert(String::from("HANDLE"), String::from("Unknown")),
+        };
+        result
+    }
+}
+
+/// Unit of a D2-01-xx measured value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum D201Unit {
+    EnergyWs = 0b000,
+    EnergyWh = 0b001,
+    EnergyKWh = 0b010,
+    PowerW = 0b011,
+    PowerKW = 0b100,
+}
+
+impl D201Unit {
+    /// Whether this is a unit of power rather than of energy
+    pub fn is_power(self) -> bool {
+        matches!(self, D201Unit::PowerW | D201Unit::PowerKW)
+    }
+}
+
+/// Util : D2-01 I/O channel field value
+fn d201_channel_description(channel: u8) -> String {
+    match channel {
+        0x1e => String::from("All output channels"),
+        0x1f => String::from("Input channel"),
+        _ => format!("{}", channel),
+    }
+}
+
+/// D2-01-xx : Electronic switches and dimmers with energy measurement (eg. micro smart plug)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum D201Reading {
+    /// CMD 0x7 : Actuator Measurement Response. `unit` is `None` if unknown, `value` is unsigned.
+    Measurement { unit: Option<D201Unit>, channel: u8, value: u32 },
+    /// CMD 0x4 : Actuator Status Response
+    Status {
+        power_failure_enabled: bool,
+        power_failure_detected: bool,
+        /// 0x00..=0x1D : output channel, 0x1E : all output channels, 0x1F : input channel
+        channel: u8,
+        /// 0 : OFF, 1..=100 : dim value [%], 127 : not valid or not set
+        output_value: u8,
+    },
+    /// Any other command
+    Unsupported { command_id: u8 },
+}
+
+impl D201Reading {
+    pub fn decode(payload: &[u8]) -> ParseEspResult<Self> {
+        check_payload_length(payload, 1)?;
+        // First we have to get CMD_ID:
+        match payload[0] & 0x0f {
+            0x07 => {
+                check_payload_length(payload, 6)?;
+                let unit = match payload[1] >> 5 {
+                    0b000 => Some(D201Unit::EnergyWs),
+                    0b001 => Some(D201Unit::EnergyWh),
+                    0b010 => Some(D201Unit::EnergyKWh),
+                    0b011 => Some(D201Unit::PowerW),
+                    0b100 => Some(D201Unit::PowerKW),
+                    _ => None,
+                };
+                Ok(D201Reading::Measurement {
+                    unit,
+                    channel: payload[1] & 0b00011111,
+                    // Measured value : DB3 (MSB) to DB0
+                    value: u32::from_be_bytes([payload[2], payload[3], payload[4], payload[5]]),
+                })
+            }
+            0x04 => {
+                check_payload_length(payload, 3)?;
+                Ok(D201Reading::Status {
+                    power_failure_enabled: bit_of_byte(7, &payload[0]),
+                    power_failure_detected: bit_of_byte(6, &payload[0]),
+                    channel: payload[1] & 0b00011111,
+                    output_value: payload[2] & 0b01111111,
+                })
+            }
+            command_id => Ok(D201Reading::Unsupported { command_id }),
+        }
+    }
+
+    /// Measured value normalized as energy in Wh or power in W, with its unit description
+    pub fn normalized_value(&self) -> Option<(&'static str, f64)> {
+        match *self {
+            D201Reading::Measurement { unit: Some(unit), value, .. } => {
+                let value = value as f64;
+                Some(match unit {
+                    D201Unit::EnergyWs => ("Energy [Wh]", value / 3600.0),
+                    D201Unit::EnergyWh => ("Energy [Wh]", value),
+                    D201Unit::EnergyKWh => ("Energy [Wh]", value * 1000.0),
+                    D201Unit::PowerW => ("Power[W]", value),
+                    D201Unit::PowerKW => ("Power[W]", value * 1000.0),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+impl From<&D201Reading> for HashMap<String, String> {
+    fn from(reading: &D201Reading) -> Self {
+        let mut parsed = HashMap::new();
+        match *reading {
+            D201Reading::Measurement { unit, channel, value } => {
+                let unit = match unit {
+                    Some(D201Unit::EnergyWs) => "Energy [Ws]",
+                    Some(D201Unit::EnergyWh) => "Energy [Wh]",
+                    Some(D201Unit::EnergyKWh) => "Energy [KWh]",
+                    Some(D201Unit::PowerW) => "Power[W]",
+                    Some(D201Unit::PowerKW) => "Power[KW]",
+                    None => "Error",
+                };
+                parsed.insert(String::from("UN"), String::from(unit));
+                parsed.insert(String::from("I/O"), d201_channel_description(channel));
+                parsed.insert(String::from("MV"), format!("{}", value));
+                if let Some((unit, value)) = reading.normalized_value() {
+                    parsed.insert(String::from("NUN"), String::from(unit));
+                    parsed.insert(String::from("NMV"), format!("{}", value));
+                }
+            }
+            D201Reading::Status {
+                power_failure_enabled,
+                power_failure_detected,
+                channel,
+                output_value,
+            } => {
+                match power_failure_enabled {
+                    false => parsed.insert(
+                        String::from("PF"),
+                        String::from("Power Failure Detection disabled/not supported"),
+                    ),
+                    true => parsed.insert(
+                        String::from("PF"),
+                        String::from("Power Failure Detection enabled"),
+                    ),
+                };
+                match power_failure_detected {
+                    false => parsed.insert(
+                        String::from("PFD"),
+                        String::from("Power Failure Detection disabled/not supported"),
+                    ),
+                    true => parsed.insert(String::from("PFD"), String::from("Power Failure Detected")),
+                };
+                // ... insert here missing EEP fields
+                parsed.insert(String::from("I/O"), d201_channel_description(channel));
+                match output_value {
+                    0x00 => parsed.insert(String::from("OV"), String::from("Output value : 0% or OFF")),
+                    0x01..=0x64 => parsed.insert(
+                        String::from("OV"),
+                        format!("Output value : {}%", output_value),
+                    ),
+                    0x65..=0x7E => parsed.insert(String::from("OV"), String::from("Not used")),
+                    _ => parsed.insert(
+                        String::from("OV"),
+                        String::from("Output value not valid / not set"),
+                    ),
+                };
+            }
+            D201Reading::Unsupported { .. } => {
+                parsed.insert(String::from("Error"), String::from("Bad CMD ID"));
+            }
+        }
+        parsed
+    }
+}
+
+/// Request of a UTE teach-in query
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UteRequest {
+    TeachIn,
+    Deletion,
+    /// Teach-in, or deletion if the device is already taught-in
+    NotSpecific,
+}
+
+/// UTE (Universal Uni- and bidirectional Teach-in) query, sent by a device to be learned (RORG 0xD4)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UteTeachIn {
+    pub bidirectional: bool,
+    pub response_expected: bool,
+    pub request: UteRequest,
+    /// Command identifier (0x0 : teach-in query)
+    pub command: u8,
+    /// Number of channels to be taught-in (0xFF : all)
+    pub channels: u8,
+    /// 11 bits manufacturer ID
+    pub manufacturer_id: u16,
+    /// EEP of the device : RORG-FUNC-TYPE
+    pub rorg: u8,
+    pub func: u8,
+    pub eep_type: u8,
+}
+
+impl UteTeachIn {
+    pub fn decode(payload: &[u8]) -> ParseEspResult<Self> {
+        check_payload_length(payload, 7)?;
+        Ok(UteTeachIn {
+            bidirectional: bit_of_byte(7, &payload[0]),
+            // 0 : response expected
+            response_expected: !bit_of_byte(6, &payload[0]),
+            request: match (payload[0] >> 4) & 0b11 {
+                0b00 => UteRequest::TeachIn,
+                0b01 => UteRequest::Deletion,
+                _ => UteRequest::NotSpecific,
+            },
+            command: payload[0] & 0x0f,
+            channels: payload[1],
+            manufacturer_id: u16::from_be_bytes([payload[3] & 0b111, payload[2]]),
+            eep_type: payload[4],
+            func: payload[5],
+            rorg: payload[6],
+        })
+    }
+}
+
+impl From<&UteTeachIn> for HashMap<String, String> {
+    fn from(query: &UteTeachIn) -> Self {
+        let mut parsed = HashMap::new();
+        match query.bidirectional {
+            false => parsed.insert(String::from("COM"), String::from("Unidirectional")),
+            true => parsed.insert(String::from("COM"), String::from("Bidirectional")),
+        };
+        match query.response_expected {
+            false => parsed.insert(String::from("RES"), String::from("No response expected")),
+            true => parsed.insert(String::from("RES"), String::from("Response expected")),
+        };
+        match query.request {
+            UteRequest::TeachIn => parsed.insert(String::from("REQ"), String::from("Teach-in request")),
+            UteRequest::Deletion => parsed.insert(String::from("REQ"), String::from("Teach-in deletion request")),
+            UteRequest::NotSpecific => parsed.insert(String::from("REQ"), String::from("Teach-in or deletion")),
+        };
+        parsed.insert(String::from("CMD"), format!("{}", query.command));
+        parsed.insert(String::from("CHN"), format!("{}", query.channels));
+        parsed.insert(String::from("MID"), format!("{:#05X}", query.manufacturer_id));
+        parsed.insert(
+            String::from("EEP"),
+            format!("{:02X}-{:02X}-{:02X}", query.rorg, query.func, query.eep_type),
+        );
+        parsed
+    }
+}
+
+/// Smart Ack telegrams, exchanged between a sensor, its postmaster (mailbox) and the controller
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmartAckTelegram {
+    /// SM_LRN_REQ (RORG 0xC6), sent by a `device` to be learned, and forwarded by a postmaster candidate
+    LearnRequest {
+        request_code: u8,
+        manufacturer_id: u16,
+        eep: EepId,
+        /// RSSI of the request received by the postmaster candidate
+        rssi: u8,
+        /// ID of the postmaster candidate, 0 if the request was received directly by the controller
+        postmaster_candidate: Address,
+        device: Address,
+    },
+    /// SM_LRN_ANS (RORG 0xC7), sent by the `controller`
+    LearnAnswer {
+        command: u8,
+        /// Time left for the device to reclaim its learn answer, in ms
+        response_time: u16,
+        confirm_code: u8,
+        controller: Address,
+    },
+    /// SM_REC (RORG 0xA7), sent by a `device` to reclaim the telegrams of its mailbox
+    Reclaim {
+        /// Learn reclaim (DB0 bit 7), or data reclaim
+        learn: bool,
+        device: Address,
+    },
+}
+
+impl SmartAckTelegram {
+    /// Decode the payload of a Smart Ack telegram of radio organization `rorg`, sent by `sender_id`
+    pub fn decode(rorg: Rorg, payload: &[u8], sender_id: Address) -> ParseEspResult<Self> {
+        match rorg {
+            Rorg::SmLrnReq => {
+                // Request code (5 bits) and manufacturer ID (11 bits), EEP, RSSI, postmaster candidate ID
+                check_payload_length(payload, 10)?;
+                Ok(SmartAckTelegram::LearnRequest {
+                    request_code: payload[0] >> 3,
+                    manufacturer_id: u16::from_be_bytes([payload[0] & 0b111, payload[1]]),
+                    eep: EepId { rorg: payload[2], func: payload[3], typ: payload[4] },
+                    rssi: payload[5],
+                    postmaster_candidate: Address::from(<[u8; 4]>::try_from(&payload[6..10]).unwrap()),
+                    device: sender_id,
+                })
+            }
+            Rorg::SmLrnAns => {
+                // Command, response time, confirm code
+                check_payload_length(payload, 4)?;
+                Ok(SmartAckTelegram::LearnAnswer {
+                    command: payload[0],
+                    response_time: u16::from_be_bytes([payload[1], payload[2]]),
+                    confirm_code: payload[3],
+                    controller: sender_id,
+                })
+            }
+            Rorg::SmRec => {
+                check_payload_length(payload, 1)?;
+                Ok(SmartAckTelegram::Reclaim { learn: bit_of_byte(7, &payload[0]), device: sender_id })
+            }
+            _ => Err(ParseEspError {
+                kind: ParseEspErrorKind::Unimplemented,
+                message: format!("{} is not a Smart Ack radio organization", rorg),
+                byte_index: None,
+                packet: payload.to_vec(),
+            }),
+        }
+    }
+}
+
+impl From<&SmartAckTelegram> for HashMap<String, String> {
+    fn from(telegram: &SmartAckTelegram) -> Self {
+        let mut parsed = HashMap::new();
+        match telegram {
+            SmartAckTelegram::LearnRequest { request_code, manufacturer_id, eep, rssi, postmaster_candidate, device } => {
+                parsed.insert(String::from("REQ"), format!("{:#04X}", request_code));
+                parsed.insert(String::from("MID"), format!("{:#05X}", manufacturer_id));
+                parsed.insert(String::from("EEP"), format!("{}", eep));
+                parsed.insert(String::from("RSSI"), format!("-{} dBm", rssi));
+                parsed.insert(String::from("POSTMASTER"), format!("{}", postmaster_candidate));
+                parsed.insert(String::from("DEVICE"), format!("{}", device));
+            }
+            SmartAckTelegram::LearnAnswer { command, response_time, confirm_code, controller } => {
+                parsed.insert(String::from("CMD"), format!("{}", command));
+                parsed.insert(String::from("RT"), format!("{} ms", response_time));
+                parsed.insert(String::from("CC"), format!("{:#04X}", confirm_code));
+                parsed.insert(String::from("CONTROLLER"), format!("{}", controller));
+            }
+            SmartAckTelegram::Reclaim { learn, device } => {
+                match learn {
+                    true => parsed.insert(String::from("REC"), String::from("Learn reclaim")),
+                    false => parsed.insert(String::from("REC"), String::from("Data reclaim")),
+                };
+                parsed.insert(String::from("DEVICE"), format!("{}", device));
+            }
+        }
+        parsed
+    }
+}
+
+/// Undecoded telegram, from a sender whose EEP is unknown
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawTelegram {
+    pub rorg: Rorg,
+    pub sender_id: Address,
+    /// Status byte, for ERP1 telegrams only
+    pub status: Option<Status>,
+    pub payload: Vec<u8>,
+}
+
+impl From<&RawTelegram> for HashMap<String, String> {
+    fn from(telegram: &RawTelegram) -> Self {
+        let mut parsed = HashMap::new();
+        parsed.insert(String::from("RORG"), format!("{}", telegram.rorg));
+        parsed.insert(String::from("SENDER"), format!("{}", telegram.sender_id));
+        if let Some(status) = telegram.status {
+            parsed.insert(String::from("STATUS"), format!("{:02X}", status.0));
+        }
+        let raw: String = telegram.payload.iter().map(|byte| format!("{:02X}", byte)).collect();
+        parsed.insert(String::from("RAW"), raw);
+        parsed
     }
-    parsed
 }
 
 // ------------------------------------------------------------------------//
 // ---------------- Enocean Message Generation ----------------------------//
 // ------------------------------------------------------------------------//
-/// Generic message 
-pub fn create_f60201_telegram(command: F602EmulateCommand)->ParseEspResult<ESP3> {
-    let mut packet: Vec<u8> = vec![0x55];
-    let usb_gw_id: Vec<u8> = vec![0, 0, 0, 0];
-    let mut data: Vec<u8> = Vec::new();
-    
-    data.push(0xf6); // choice
-    match command {
-        F602EmulateCommand::MoveBlindClosed => {
-            data.extend_from_slice(&[0x10]); 
-        },
-        F602EmulateCommand::MoveBlindOpen =>{
-            data.extend_from_slice(&[0x30]);      
-        }
-    }
-    data.extend_from_slice(&usb_gw_id);
-    data.push(0x30); //status T21 NU to 1 
-    let data_length: u8 = data.len() as u8;
+/// RPS (eg. rocker switch) telegram from `sender`, with `data` the button action (DB0) and `status` the T21/NU flags.
+///
+/// `sender` 0 means the chip ID of the gateway, `destination` is usually the broadcast address FF:FF:FF:FF.
+pub fn create_rps_telegram(sender: Address, data: u8, status: u8, destination: Address) -> ParseEspResult<ESP3> {
+    let mut erp1: Vec<u8> = vec![u8::from(Rorg::Rps), data]; // choice
+    erp1.extend_from_slice(&<[u8; 4]>::from(sender));
+    erp1.push(status);
 
-    // OPT_DATA
+    // Send flag, destination, dBm, security level
     let mut opt_data: Vec<u8> = vec![0x03];
-    opt_data.extend_from_slice(&[0xff,0xff,0xff,0xff]);
-    opt_data.push(0xff);
-    opt_data.push(0x00);
-    let opt_len: u8 = opt_data.len() as u8;
-
-    // HEADER
-    let mut header: Vec<u8> = Vec::new();
-    header.push(0x00); //data length MSB
-    header.push(data_length);
-    header.push(opt_len);
-    header.push(0x01); //packet type radio
+    opt_data.extend_from_slice(&<[u8; 4]>::from(destination));
+    opt_data.extend_from_slice(&[0xff, 0x00]);
 
-    // CRCs
-    let crc_header = compute_crc8(&header);
-    println!("{}",crc_header);
-    data.append(&mut opt_data);
-    let crc_data = compute_crc8(&data);
-    println!("{}",crc_data);
+    let frame = ESP3Frame::assemble(0x01, &erp1, &opt_data);
+    esp3_of_enocean_message(frame.borrow())
+}
 
-    packet.extend_from_slice(&header);
-    packet.push(crc_header);
-    packet.extend_from_slice(&data);
-    packet.extend_from_slice(&opt_data);
-    packet.push(crc_data);
-    esp3_of_enocean_message(&packet)
+/// F6-02-01 rocker switch press, sent by the gateway
+pub fn create_f60201_telegram(command: F602EmulateCommand)->ParseEspResult<ESP3> {
+    let data = match command {
+        F602EmulateCommand::MoveBlindClosed => 0x10, // AI pressed
+        F602EmulateCommand::MoveBlindOpen => 0x30,   // A0 pressed
+    };
+    // status T21 NU to 1
+    create_rps_telegram(Address::from([0, 0, 0, 0]), data, 0x30, Address::from([0xff, 0xff, 0xff, 0xff]))
 }
 
 /// UTE telegram acceptation
 pub fn create_smart_plug_teach_in_accepted_response_packet(socket_id: [u8; 4]) -> ParseEspResult<ESP3> {
     // Data
-    let rorg = 0xd4;
+    let rorg = u8::from(Rorg::Ute);
     // let bidirectional_comm = [0,1];
     // let reponse_code= [0,1] ; //teachin accepted
     let infos = 0xd1;
@@ -339,48 +1408,99 @@ pub fn create_smart_plug_teach_in_accepted_response_packet(socket_id: [u8; 4]) -
 
     let crc_header = compute_crc8(&header);
 
-    let mut esp3_packet: Vec<u8> = vec![0x55];
-    esp3_packet.append(&mut header);
-    esp3_packet.push(crc_header);
-    esp3_packet.append(&mut data);
-    esp3_packet.append(&mut opt_data);
-    esp3_packet.push(crc_data);
-    // println!("PACKET : {:#x?}", esp3_packet);
-    esp3_of_enocean_message(&esp3_packet)
+    let mut esp3_packet: Vec<u8> = vec![0x55];
+    esp3_packet.append(&mut header);
+    esp3_packet.push(crc_header);
+    esp3_packet.append(&mut data);
+    esp3_packet.append(&mut opt_data);
+    esp3_packet.push(crc_data);
+    // println!("PACKET : {:#x?}", esp3_packet);
+    esp3_of_enocean_message(&esp3_packet)
+}
+/// UTE teach-in response to a query received from `device_id`.
+///
+/// The response code is "EEP not supported" if the EEP of the device is not supported by this lib, even when `accept` is true.
+/// `gateway_id` is the sender ID of the response (0 : use the chip ID of the gateway).
+pub fn create_ute_teach_in_response(
+    request: &UteTeachIn,
+    device_id: Address,
+    accept: bool,
+    gateway_id: Address,
+) -> ParseEspResult<ESP3> {
+    let response_code: u8 = match (accept, request.request) {
+        (false, _) => 0b00, // Request not accepted
+        _ if EEP::from_profile(request.rorg, request.func, request.eep_type).is_none() => 0b11,
+        (true, UteRequest::Deletion) => 0b10, // Deletion accepted
+        (true, _) => 0b01,                    // Teach-in accepted
+    };
+    // Bidirectional communication, response code, CMD 0x1 : teach-in response
+    let db_6 = (request.bidirectional as u8) << 7 | response_code << 4 | 0x01;
+    let manufacturer_id = request.manufacturer_id.to_be_bytes();
+
+    let mut data: Vec<u8> = vec![u8::from(Rorg::Ute), db_6, request.channels, manufacturer_id[1], manufacturer_id[0] & 0b111];
+    data.extend_from_slice(&[request.eep_type, request.func, request.rorg]);
+    data.extend_from_slice(&<[u8; 4]>::from(gateway_id));
+    data.push(0x00); // status
+
+    // Send flag, destination, dBm, security level
+    let mut opt_data: Vec<u8> = vec![0x03];
+    opt_data.extend_from_slice(&<[u8; 4]>::from(device_id));
+    opt_data.extend_from_slice(&[0xff, 0x00]);
+
+    let frame = ESP3Frame::assemble(0x01, &data, &opt_data);
+    esp3_of_enocean_message(frame.borrow())
+}
+/// Util : `InvalidValue` error for a setting out of range
+fn invalid_value(message: &str) -> ParseEspError {
+    ParseEspError {
+        kind: ParseEspErrorKind::InvalidValue,
+        message: String::from(message),
+        byte_index: None,
+        packet: vec![],
+    }
+}
+
+/// Util : D2-01 output channels go from 0 to 0x1D, 0x1E being all output channels
+fn check_output_channel(channel: u8) -> ParseEspResult<()> {
+    match channel {
+        0..=0x1e => Ok(()),
+        _ => Err(invalid_value("Channel must be between 0 and 0x1E")),
+    }
 }
+
 /// SmartPLug commands creation
 pub fn create_smart_plug_command(socket_id: [u8; 4], command: D201CommandList) -> ParseEspResult<ESP3> {
     let mut packet: Vec<u8> = vec![0x55];
     let mut usb_gw_id: Vec<u8> = vec![0, 0, 0, 0];
-    let mut data: Vec<u8> = Vec::new();
+    let mut data: Vec<u8> = vec![u8::from(Rorg::Vld)];
     match command {
         D201CommandList::Off => {
-            data.extend_from_slice(&[0xd2, 0x01, 0x00, 0x00]); // 01 = CMD ID // 00 00 = output 0 to 0
+            data.extend_from_slice(&[0x01, 0x00, 0x00]); // 01 = CMD ID // 00 00 = output 0 to 0
         }
         D201CommandList::On => {
-            data.extend_from_slice(&[0xd2, 0x01, 0x00, 0x01]); // 01 = CMD ID // 00 00 = output 0 to 1
+            data.extend_from_slice(&[0x01, 0x00, 0x01]); // 01 = CMD ID // 00 00 = output 0 to 1
         }
         D201CommandList::QueryEnergy => {
-            data.extend_from_slice(&[0xd2, 0x06, 0x00]); // 06 = CMD ID // query Energy (Default config = Wh)
+            data.extend_from_slice(&[0x06, 0x00]); // 06 = CMD ID // query Energy (Default config = Wh)
         }
         D201CommandList::QueryPower => {
-            data.extend_from_slice(&[0xd2, 0x06, 0x20]); // 06 = CMD ID // query power (Default Config = W)
+            data.extend_from_slice(&[0x06, 0x20]); // 06 = CMD ID // query power (Default Config = W)
         }
-        D201CommandList::DefaultConfig => {
-            let db_4: u8 = 0b10100000; // b0: autoreporting , b1 : no reset, b2 : power measurement, then channel nb (0)
-            let db_3: u8 = 0x33; // B0-3 = report delta 3w, b4-7: unit = watts
-            let db_2: u8 = 0x00; // MSB of report delta, 0 here
-            let db_1: u8 = 0x06; // max time between 2 messages = 6 * 10 secondes
-            let db_0: u8 = 0x01; // min time between 2 messages = 1 * 1 second
-
-            // DATA
-            let mut data: Vec<u8> = vec![0xd2, 0x05]; // 05 = CMD ID
-            data.push(db_4);
-            data.push(db_3);
-            data.push(db_2);
-            data.push(db_1);
-            data.push(db_0);
+        D201CommandList::SetDimValue { channel, percent, dim_timer } => {
+            check_output_channel(channel)?;
+            if percent > 100 {
+                return Err(invalid_value("Dim value must be between 0 and 100%"));
+            }
+            if dim_timer.is_some_and(|timer| !(1..=3).contains(&timer)) {
+                return Err(invalid_value("Dim timer must be between 1 and 3"));
+            }
+            // 01 = CMD ID // dim value (b7-5) + I/O channel (b4-0) // output value
+            data.extend_from_slice(&[0x01, dim_timer.unwrap_or(0) << 5 | channel, percent]);
         }
+        D201CommandList::QueryStatus { channel } => {
+            data.extend_from_slice(&[0x03, channel & 0x1f]); // 03 = CMD ID // I/O channel
+        }
+        D201CommandList::DefaultConfig => return create_smart_plug_default_config_packet(socket_id),
     }
     //DATA
     data.append(&mut usb_gw_id);
@@ -412,61 +1532,196 @@ pub fn create_smart_plug_command(socket_id: [u8; 4], command: D201CommandList) -
     packet.push(crc_data);
     esp3_of_enocean_message(&packet)
 }
-/// Config a D2010E micro smart plug 
-pub fn create_smart_plug_default_config_packet(socket_id: [u8; 4]) -> ParseEspResult<ESP3>{
-    let mut result: Vec<u8> = vec![0x55];
-    let mut usb_gw_id: Vec<u8> = vec![0, 0, 0, 0];
+/// Reporting settings of a D2-01 actuator (CMD 0x5 : Actuator Set Measurement)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReportConfig {
+    /// Report measurements automatically, or only when queried
+    pub auto_reporting: bool,
+    /// I/O channel (0x1E : all output channels)
+    pub channel: u8,
+    /// Unit of the reports, which also selects energy or power measurement
+    pub unit: D201Unit,
+    /// Change of the measured value (in `unit`) triggering a report, on 12 bits
+    pub report_delta: u16,
+    /// Maximum time between two reports, in 10s steps (1..=255)
+    pub max_time: u8,
+    /// Minimum time between two reports, in seconds (1..=255)
+    pub min_time: u8,
+}
 
-    let db_4: u8 = 0b10100000; // b0: autoreporting , b1 : no reset, b2 : power measurement, then channel nb (0)
-    let db_3: u8 = 0x33; // B0-3 = report delta 3w, b4-7: unit = watts
-    let db_2: u8 = 0x00; // MSB of report delta, 0 here
-    let db_1: u8 = 0x06; // max time between 2 messages = 6 * 10 secondes
-    let db_0: u8 = 0x01; // min time between 2 messages = 1 * 1 second
-
-    // DATA
-    let mut data: Vec<u8> = vec![0xd2, 0x05]; // 05 = CMD ID
-    data.push(db_4);
-    data.push(db_3);
-    data.push(db_2);
-    data.push(db_1);
-    data.push(db_0);
-    data.append(&mut usb_gw_id);
-    data.push(0x00); //status
+impl Default for ReportConfig {
+    /// Power of channel 0 reported every 3W change, every 1 to 60s
+    fn default() -> Self {
+        ReportConfig { auto_reporting: true, channel: 0, unit: D201Unit::PowerW, report_delta: 3, max_time: 6, min_time: 1 }
+    }
+}
 
-    let data_length: u8 = data.len() as u8;
+impl ReportConfig {
+    /// DB4 to DB0 of the CMD 0x5 telegram, or an `InvalidValue` error for out of range settings
+    fn to_bytes(self) -> ParseEspResult<[u8; 5]> {
+        check_output_channel(self.channel)?;
+        if self.report_delta > 0x0fff {
+            return Err(invalid_value("Report delta must fit in 12 bits"));
+        }
+        if self.max_time == 0 || self.min_time == 0 {
+            return Err(invalid_value("Min and max times between reports must be at least 1"));
+        }
+        // b7 : autoreporting, b6 : no reset, b5 : power (1) or energy (0) measurement, b4-0 : channel
+        let db_4: u8 = (self.auto_reporting as u8) << 7 | (self.unit.is_power() as u8) << 5 | self.channel;
+        let db_3: u8 = ((self.report_delta & 0x0f) as u8) << 4 | self.unit as u8; // b7-4 : LSB of report delta, b2-0 : unit
+        let db_2: u8 = (self.report_delta >> 4) as u8; // MSB of report delta
+        Ok([db_4, db_3, db_2, self.max_time, self.min_time])
+    }
+}
 
-    // OPT_DATA
+/// Config the reporting of a D2010E micro smart plug
+pub fn create_smart_plug_config_packet(socket_id: [u8; 4], config: &ReportConfig) -> ParseEspResult<ESP3> {
+    let mut data: Vec<u8> = vec![u8::from(Rorg::Vld), 0x05]; // 05 = CMD ID
+    data.extend_from_slice(&config.to_bytes()?);
+    data.extend_from_slice(&[0, 0, 0, 0]); // usb gateway id
+    data.push(0x00); //status
+
+    // Send flag, destination, dBm, security level
     let mut opt_data: Vec<u8> = vec![0x03];
     opt_data.extend_from_slice(&socket_id);
-    opt_data.push(0xff);
-    opt_data.push(0x00);
-    let opt_len: u8 = opt_data.len() as u8;
+    opt_data.extend_from_slice(&[0xff, 0x00]);
 
-    // HEADER
-    let mut header: Vec<u8> = Vec::new();
-    header.push(0x00); //data length= 16 bits)
-    header.push(data_length);
-    header.push(opt_len);
-    header.push(0x01); //packet type radio
+    let frame = ESP3Frame::assemble(0x01, &data, &opt_data);
+    esp3_of_enocean_message(frame.borrow())
+}
 
-    // CRCs
-    let crc_header = compute_crc8(&header);
-    data.append(&mut opt_data);
-    let crc_data = compute_crc8(&data);
+/// Config a D2010E micro smart plug with the default `ReportConfig`
+pub fn create_smart_plug_default_config_packet(socket_id: [u8; 4]) -> ParseEspResult<ESP3> {
+    create_smart_plug_config_packet(socket_id, &ReportConfig::default())
+}
+
+/// A D2-01 micro smart plug, building the telegrams to send to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SmartPlug {
+    pub address: Address,
+}
+
+impl SmartPlug {
+    pub fn new(address: Address) -> Self {
+        SmartPlug { address }
+    }
 
-    result.append(&mut header);
-    result.push(crc_header);
-    result.append(&mut data);
-    result.append(&mut opt_data);
-    result.push(crc_data);
+    /// Response accepting the UTE teach-in query of the plug
+    pub fn teach_in_accept(&self) -> ParseEspResult<ESP3> {
+        create_smart_plug_teach_in_accepted_response_packet(self.address.into())
+    }
+
+    /// Set how the plug reports its power
+    pub fn configure_reporting(&self, config: ReportConfig) -> ParseEspResult<ESP3> {
+        create_smart_plug_config_packet(self.address.into(), &config)
+    }
+
+    pub fn on(&self) -> ParseEspResult<ESP3> {
+        create_smart_plug_command(self.address.into(), D201CommandList::On)
+    }
+
+    pub fn off(&self) -> ParseEspResult<ESP3> {
+        create_smart_plug_command(self.address.into(), D201CommandList::Off)
+    }
 
-    esp3_of_enocean_message(&result)
+    pub fn query_power(&self) -> ParseEspResult<ESP3> {
+        create_smart_plug_command(self.address.into(), D201CommandList::QueryPower)
+    }
 }
 
 /// Unit Tests
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    /// Util : build a received ERP1 telegram from its data
+    fn erp1_telegram(data: &[u8]) -> ESP3 {
+        esp3_of_enocean_message(&build_message(0x01, data, &[1, 255, 255, 255, 255, 54, 0])).unwrap()
+    }
+    #[test]
+    fn given_4bs_teach_in_telegram_then_parse_and_register_its_eep() {
+        // A50401 sensor (NodOn, manufacturer 0x046) when its learn button is pushed
+        let teach_in = erp1_telegram(&[165, 16, 8, 70, 128, 5, 17, 114, 247, 0]);
+        assert!(is_teach_in(&teach_in));
+        assert_eq!(
+            parse_4bs_teach_in(&[16, 8, 70, 128]),
+            Some(("A5-04-01".parse::<EepId>().unwrap(), 0x046))
+        );
+        let mut registry = DeviceRegistry::new();
+        assert_eq!(registry.register_teach_in(&teach_in), Some(EEP::A50401));
+        assert_eq!(registry.lookup(&[5, 17, 114, 247]), Some(&EEP::A50401));
+
+        // Data telegram, and teach-in without EEP
+        assert_eq!(parse_4bs_teach_in(&[0, 229, 204, 10]), None);
+        assert_eq!(parse_4bs_teach_in(&[16, 8, 70, 0]), None);
+        let data = erp1_telegram(&[165, 0, 229, 204, 10, 5, 17, 114, 247, 0]);
+        assert_eq!(DeviceRegistry::new().register_teach_in(&data), None);
+    }
+
+    #[test]
+    fn given_eep_and_commands_then_use_them_as_map_keys() {
+        let mut names: HashMap<EEP, String> = HashMap::new();
+        names.insert(EEP::A50401, String::from("Temperature and humidity sensor"));
+        names.insert(EEP::D2010E, String::from("Micro smart plug"));
+        assert_eq!(names.get(&EEP::from_id("D2-01-0E".parse().unwrap()).unwrap()).unwrap(), "Micro smart plug");
+
+        let commands: HashMap<D201CommandList, &str> = HashMap::from([(D201CommandList::On, "on"), (D201CommandList::Off, "off")]);
+        assert_eq!(commands[&D201CommandList::On], "on");
+        assert_ne!(F602EmulateCommand::MoveBlindOpen, F602EmulateCommand::MoveBlindClosed);
+    }
+
+    #[test]
+    fn given_smart_plug_then_build_the_same_telegrams_as_the_create_functions() {
+        let socket_id = [0x05, 0x0a, 0x3d, 0x6a];
+        let plug = SmartPlug::new(Address::from(socket_id));
+        let bytes = |esp: ParseEspResult<ESP3>| Vec::from(&esp.unwrap());
+
+        assert_eq!(bytes(plug.teach_in_accept()), bytes(create_smart_plug_teach_in_accepted_response_packet(socket_id)));
+        assert_eq!(bytes(plug.on()), bytes(create_smart_plug_command(socket_id, D201CommandList::On)));
+        assert_eq!(bytes(plug.off()), bytes(create_smart_plug_command(socket_id, D201CommandList::Off)));
+        assert_eq!(bytes(plug.query_power()), bytes(create_smart_plug_command(socket_id, D201CommandList::QueryPower)));
+
+        // Formerly hardcoded default config : autoreporting of power, every 3W change, every 1 to 60s
+        let default_config = ESP3Frame::assemble(
+            0x01,
+            &[0xd2, 0x05, 0b10100000, 0x33, 0x00, 0x06, 0x01, 0, 0, 0, 0, 0x00],
+            &[0x03, 0x05, 0x0a, 0x3d, 0x6a, 0xff, 0x00],
+        );
+        let default_config: &[u8] = default_config.borrow();
+        assert_eq!(bytes(plug.configure_reporting(ReportConfig::default())), default_config);
+        assert_eq!(bytes(create_smart_plug_default_config_packet(socket_id)), default_config);
+    }
+
+    #[test]
+    fn given_report_configs_then_encode_their_settings_or_reject_them() {
+        let config_bytes = |config: ReportConfig| match create_smart_plug_config_packet([0x05, 0x0a, 0x3d, 0x6a], &config).unwrap().data {
+            DataType::Erp1Data { payload, .. } => payload,
+            _ => panic!("Expected an ERP1 telegram"),
+        };
+        // Energy of all channels in Wh, reported when queried, every 0x123 Wh change, every 5 to 2550s
+        let config = ReportConfig {
+            auto_reporting: false,
+            channel: 0x1e,
+            unit: D201Unit::EnergyWh,
+            report_delta: 0x123,
+            max_time: 255,
+            min_time: 5,
+        };
+        assert_eq!(config_bytes(config), vec![0x05, 0x1e, 0x31, 0x12, 0xff, 0x05]);
+        // Auto reported power of channel 1 in kW
+        let config = ReportConfig { channel: 1, unit: D201Unit::PowerKW, report_delta: 0xfff, ..ReportConfig::default() };
+        assert_eq!(config_bytes(config), vec![0x05, 0xa1, 0xf4, 0xff, 0x06, 0x01]);
+
+        for config in [
+            ReportConfig { report_delta: 0x1000, ..ReportConfig::default() },
+            ReportConfig { channel: 0x1f, ..ReportConfig::default() },
+            ReportConfig { min_time: 0, ..ReportConfig::default() },
+        ] {
+            let error = create_smart_plug_config_packet([0x05, 0x0a, 0x3d, 0x6a], &config).unwrap_err();
+            assert_eq!(error.kind, ParseEspErrorKind::InvalidValue);
+        }
+    }
+
     // ESP3 - ERP1 - EEP specified fields PARSING
     // --------------------------------------------------------------------
     #[test]
@@ -507,6 +1762,33 @@ mod tests {
         let results = parse_erp1_payload(&esp3_packet).unwrap();
 
         assert_eq!(results.get("BTN").unwrap(), &String::from("Pressed"));
+        assert_eq!(results.get("R1").unwrap(), &String::from("B0"));
+        assert_eq!(results.get("EB").unwrap(), &String::from("Pressed"));
+    }
+
+    #[test]
+    fn given_f60201_rocker_payloads_then_decode_rocker_actions() {
+        // NodOn A0 (-) pushed
+        let reading = F60201Reading::decode(&[48]).unwrap();
+        assert_eq!(reading.rocker_1, Some(RockerAction::A0));
+        assert!(reading.energy_bow_pressed);
+        assert_eq!(reading.rocker_2, None);
+
+        // NodOn button labelled B1 (cercle plein) pushed, then released : it sends rocker action A1, 0b000_1_0000
+        let results = HashMap::from(&F60201Reading::decode(&[16]).unwrap());
+        assert_eq!(results.get("R1").unwrap(), &String::from("A1"));
+        assert_eq!(results.get("EB").unwrap(), &String::from("Pressed"));
+        assert_eq!(results.get("SA").unwrap(), &String::from("No 2nd action"));
+        let results = HashMap::from(&F60201Reading::decode(&[0]).unwrap());
+        assert_eq!(results.get("R1").unwrap(), &String::from("None"));
+        assert_eq!(results.get("EB").unwrap(), &String::from("Released"));
+        assert_eq!(results.get("BTN").unwrap(), &String::from("Released"));
+
+        // B1 and B0 pushed together : 0b010_1_011_1
+        let results = HashMap::from(&F60201Reading::decode(&[0x57]).unwrap());
+        assert_eq!(results.get("R1").unwrap(), &String::from("B1"));
+        assert_eq!(results.get("R2").unwrap(), &String::from("B0"));
+        assert_eq!(results.get("SA").unwrap(), &String::from("2nd action valid"));
     }
 
     #[test]
@@ -531,22 +1813,465 @@ mod tests {
         let results = parse_erp1_payload(&esp3_packet).unwrap();
         assert_eq!(results.get("MV").unwrap(), &String::from("19"));
         assert_eq!(results.get("UN").unwrap(), &String::from("Power[W]"));
+        assert_eq!(results.get("NMV").unwrap(), &String::from("19"));
+        assert_eq!(results.get("NUN").unwrap(), &String::from("Power[W]"));
+    }
+
+    #[test]
+    fn given_d2010e_energy_report_above_16m_then_parse_full_32_bits_value() {
+        // Unit = Energy [KWh] (0b010), I/O channel 0, value = 0x01020304
+        let results = HashMap::from(&D201Reading::decode(&[0x07, 0x40, 0x01, 0x02, 0x03, 0x04]).unwrap());
+        assert_eq!(results.get("MV").unwrap(), &String::from("16909060"));
+        assert_eq!(results.get("UN").unwrap(), &String::from("Energy [KWh]"));
+        assert_eq!(results.get("NMV").unwrap(), &String::from("16909060000"));
+        assert_eq!(results.get("NUN").unwrap(), &String::from("Energy [Wh]"));
+
+        // Unit = Energy [Ws] (0b000), I/O channel 1
+        let results = HashMap::from(&D201Reading::decode(&[0x07, 0x01, 0x00, 0x00, 0x1c, 0x20]).unwrap());
+        assert_eq!(results.get("I/O").unwrap(), &String::from("1"));
+        assert_eq!(results.get("NMV").unwrap(), &String::from("2"));
+    }
+
+    #[test]
+    fn given_too_short_d2010e_payload_then_return_error_entry() {
+        let mut registry = DeviceRegistry::new();
+        registry.register([0x05, 0x0a, 0x3d, 0x6a], EEP::D2010E);
+        for payload in [vec![], vec![0x07, 0x60, 0x00], vec![0x04, 0x60]] {
+            let mut data = vec![0xd2];
+            data.extend_from_slice(&payload);
+            data.extend_from_slice(&[0x05, 0x0a, 0x3d, 0x6a, 0x00]);
+            let results = parse_erp1_payload_with(&erp1_telegram(&data), &registry).unwrap();
+            assert_eq!(results.get("Error").unwrap(), &String::from("Payload too short"));
+        }
+    }
+
+    #[test]
+    fn given_too_short_d2010e_payload_then_return_error() {
+        for payload in [vec![], vec![0x07, 0x60, 0x00], vec![0x07, 0x60, 0x00, 0x00, 0x00], vec![0x04, 0x60]] {
+            let error = D201Reading::decode(&payload).unwrap_err();
+            assert_eq!(error.kind, ParseEspErrorKind::IncompleteMessage);
+            assert_eq!(error.message, String::from("Payload too short"));
+        }
+    }
+    #[test]
+    fn given_registered_device_then_parse_its_payload_with_registry() {
+        // A50401 telegram, from an unknown sender id [1, 2, 3, 4]
+        let esp3_packet = erp1_telegram(&[165, 0, 229, 204, 10, 1, 2, 3, 4, 0]);
+
+        assert!(parse_erp1_payload(&esp3_packet).is_err());
+
+        let mut registry = DeviceRegistry::new();
+        assert!(registry.register([1, 2, 3, 4], EEP::A50401).is_none());
+        let results = parse_erp1_payload_with(&esp3_packet, &registry).unwrap();
+        assert_eq!(results.get("HUM").unwrap(), &String::from("91.6"));
+    }
+
+    #[test]
+    fn given_a50402_and_a50403_telegrams_then_scale_with_their_ranges() {
+        // A5-04-02, DB2 = 125 -> 50%, DB1 = 125 -> 20°C, DB0 = 0x0a : data telegram with temperature sensor
+        let esp3_packet = erp1_telegram(&[165, 0, 125, 125, 0x0a, 5, 17, 114, 248, 0]);
+        let mut registry = DeviceRegistry::new();
+        registry.register([5, 17, 114, 248], EEP::A50402);
+        let results = parse_erp1_payload_with(&esp3_packet, &registry).unwrap();
+        assert_eq!(results.get("TMP").unwrap(), &String::from("20"));
+        assert_eq!(results.get("HUM").unwrap(), &String::from("50"));
+
+        let range = |db1: u8| A50401Reading::decode_a50402(&[0, 0, db1, 0x08]).unwrap().temperature;
+        assert_eq!(range(0), -20.0);
+        assert_eq!(range(250), 60.0);
+
+        // A5-04-03, DB3 = 255 -> 100%, DB2..DB1 = 0x3ff -> 60°C
+        registry.register([5, 17, 114, 248], EEP::A50403);
+        let esp3_packet = erp1_telegram(&[165, 255, 0x03, 0xff, 0x08, 5, 17, 114, 248, 0]);
+        let results = parse_erp1_payload_with(&esp3_packet, &registry).unwrap();
+        assert_eq!(results.get("TMP").unwrap(), &String::from("60"));
+        assert_eq!(results.get("HUM").unwrap(), &String::from("100"));
+        assert_eq!(A50401Reading::decode_a50403(&[0, 0, 0, 0x08]).unwrap().temperature, -20.0);
+    }
+
+    #[test]
+    fn given_valid_a50205_esp3_packet_then_parse_temperature() {
+        // DB1 = 0x66 -> 40 - 102 * 40 / 255 = 24°C, DB0 = 0x08 : data telegram
+        let esp3_packet = erp1_telegram(&[0xa5, 0x00, 0x00, 0x66, 0x08, 0x01, 0x82, 0x5d, 0xab, 0x00]);
+        let mut registry = DeviceRegistry::new();
+        registry.register([0x01, 0x82, 0x5d, 0xab], EEP::A50205);
+
+        let results = parse_erp1_payload_with(&esp3_packet, &registry).unwrap();
+        assert_eq!(results.get("TMP").unwrap(), &String::from("24"));
+        assert_eq!(results.get("LRNB").unwrap(), &String::from("Data telegram"));
+
+        let results = HashMap::from(&A50205Reading::decode(&[0x00, 0x00, 0xff, 0x00]).unwrap());
+        assert_eq!(results.get("TMP").unwrap(), &String::from("0"));
+        assert_eq!(results.get("LRNB").unwrap(), &String::from("Teach-in telegram"));
+    }
+
+    #[test]
+    fn given_f61000_window_handle_payloads_then_decode_positions() {
+        let esp3_packet = erp1_telegram(&[0xf6, 0xf0, 0x01, 0x83, 0x1a, 0x29, 0x20]);
+        let mut registry = DeviceRegistry::new();
+        registry.register([0x01, 0x83, 0x1a, 0x29], EEP::F61000);
+        let results = parse_erp1_payload_with(&esp3_packet, &registry).unwrap();
+        assert_eq!(results.get("HANDLE").unwrap(), &String::from("Closed"));
+
+        let handle = |db0: u8| F61000Reading::decode(&[db0]).unwrap().handle;
+        assert_eq!(handle(0xf0), Some(HandlePosition::Closed));
+        assert_eq!(handle(0xc0), Some(HandlePosition::Open));
+        assert_eq!(handle(0xe0), Some(HandlePosition::Open));
+        assert_eq!(handle(0xd0), Some(HandlePosition::Tilted));
+        assert_eq!(handle(0x00), None);
+        assert_eq!(
+            HashMap::from(&F61000Reading::decode(&[0x10]).unwrap()).get("HANDLE").unwrap(),
+            &String::from("Unknown")
+        );
+    }
+
+    #[test]
+    fn given_valid_a50801_esp3_packet_then_parse_light_temperature_and_occupancy() {
+        // DB3 = 150 -> 3V, DB2 = 100 -> 200lx, DB1 = 110 -> 22°C, DB0 = 0x09 : data telegram, PIR on, button released
+        let esp3_packet = erp1_telegram(&[0xa5, 150, 100, 110, 0x09, 0x01, 0x85, 0x6a, 0x21, 0x00]);
+        let mut registry = DeviceRegistry::new();
+        registry.register([0x01, 0x85, 0x6a, 0x21], EEP::A50801);
+
+        let results = parse_erp1_payload_with(&esp3_packet, &registry).unwrap();
+        assert_eq!(results.get("SVC").unwrap(), &String::from("3"));
+        assert_eq!(results.get("ILL").unwrap(), &String::from("200"));
+        assert_eq!(results.get("TMP").unwrap(), &String::from("22"));
+        assert_eq!(results.get("PIR").unwrap(), &String::from("PIR on"));
+        assert_eq!(results.get("OCC").unwrap(), &String::from("Button released"));
+        assert_eq!(results.get("LRNB").unwrap(), &String::from("Data telegram"));
+
+        let reading = A50801Reading::decode(&[0, 255, 255, 0x0a]).unwrap();
+        assert_eq!((reading.illumination, reading.temperature), (510.0, 51.0));
+        assert!(!reading.motion_detected && reading.occupancy_button_pressed);
+    }
+
+    #[test]
+    fn given_valid_a52001_esp3_packet_then_parse_valve_status() {
+        // DB3 = 40 % open, DB2 = 0x70 : energy input enabled, storage charged, battery OK, DB1 = 102 -> 16°C, DB0 = data
+        let esp3_packet = erp1_telegram(&[0xa5, 40, 0x70, 102, 0x08, 0x01, 0x9a, 0x4b, 0x07, 0x00]);
+        let mut registry = DeviceRegistry::new();
+        registry.register([0x01, 0x9a, 0x4b, 0x07], EEP::A52001);
+
+        let results = parse_erp1_payload_with(&esp3_packet, &registry).unwrap();
+        assert_eq!(results.get("CV").unwrap(), &String::from("40"));
+        assert_eq!(results.get("TMP").unwrap(), &String::from("16"));
+        assert_eq!(results.get("ENIE").unwrap(), &String::from("true"));
+        assert_eq!(results.get("BCAP").unwrap(), &String::from("Battery OK"));
+        assert_eq!(results.get("DWO").unwrap(), &String::from("false"));
+        assert_eq!(results.get("ACTSTAT").unwrap(), &String::from("Actuator OK"));
+        assert_eq!(results.get("LRNB").unwrap(), &String::from("Data telegram"));
+
+        // Battery to change, window open, actuator obstructed
+        let reading = A52001Reading::decode(&[0, 0x63, 0, 0x08]).unwrap();
+        assert!(reading.battery_low && reading.window_open && reading.actuator_obstructed);
+        assert!(!reading.contact_cover_open);
     }
+
+    #[test]
+    fn given_valid_a51201_esp3_packet_then_parse_scaled_meter_value() {
+        // DB3..DB1 = 0x01e240 = 123456, DB0 = 0x1a : tariff 1, data telegram, cumulative, x/100
+        let esp3_packet = erp1_telegram(&[0xa5, 0x01, 0xe2, 0x40, 0x1a, 0x01, 0x94, 0xe3, 0xb9, 0x00]);
+        let mut registry = DeviceRegistry::new();
+        registry.register([0x01, 0x94, 0xe3, 0xb9], EEP::A51201);
+
+        let results = parse_erp1_payload_with(&esp3_packet, &registry).unwrap();
+        assert_eq!(results.get("MV").unwrap(), &String::from("1234.56"));
+        assert_eq!(results.get("DT").unwrap(), &String::from("Cumulative value [kWh]"));
+        assert_eq!(results.get("DIV").unwrap(), &String::from("x/100"));
+        assert_eq!(results.get("TI").unwrap(), &String::from("1"));
+        assert_eq!(results.get("LRNB").unwrap(), &String::from("Data telegram"));
+
+        // DB0 = 0x0c : current value, no divisor
+        let reading = A51201Reading::decode(&[0x00, 0x01, 0xf4, 0x0c]).unwrap();
+        assert!(!reading.cumulative);
+        assert_eq!(reading.value(), 500.0);
+    }
+
+    #[test]
+    fn given_d50001_door_contact_telegrams_then_parse_contact_state() {
+        // Door contact, DB0 = 0x09 : data telegram, contact closed
+        let closed = erp1_telegram(&[0xd5, 0x09, 0x01, 0x92, 0x3d, 0xa8, 0x00]);
+        let results = parse_erp1_payload(&closed).unwrap();
+        assert_eq!(results.get("CO").unwrap(), &String::from("closed"));
+        assert_eq!(results.get("LRNB").unwrap(), &String::from("not pressed"));
+
+        // DB0 = 0x08 : data telegram, contact open
+        let open = erp1_telegram(&[0xd5, 0x08, 0x01, 0x92, 0x3d, 0xa8, 0x00]);
+        let results = parse_erp1_payload(&open).unwrap();
+        assert_eq!(results.get("CO").unwrap(), &String::from("open"));
+
+        // DB0 = 0x00 : teach-in telegram
+        let reading = D50001Reading::decode(&[0x00]).unwrap();
+        assert!(reading.learn_button_pressed);
+        assert!(!reading.contact_closed);
+
+        assert!(D50001Reading::decode(&[]).is_err());
+    }
+
+    #[test]
+    fn given_1bs_contact_telegram_then_parse_its_data_byte_and_learn_bit() {
+        let closed = erp1_telegram(&[0xd5, 0x09, 0x01, 0x92, 0x3d, 0xa8, 0x00]);
+        let payload = match &closed.data {
+            DataType::Erp1Data { rorg: Rorg::Bs1, payload, .. } => payload,
+            _ => panic!("Expected a 1BS telegram"),
+        };
+        assert_eq!(parse_bs1(payload).unwrap(), Bs1Telegram { data: 0x09, learn: false });
+        assert_eq!(parse_bs1(&[0x01]).unwrap(), Bs1Telegram { data: 0x01, learn: true });
+        assert!(parse_bs1(&[]).is_err());
+    }
+
+    #[test]
+    fn given_valid_a50701_esp3_packet_when_motion_detected_then_parse_all_data() {
+        // DB3 = 200 -> 4V, DB1 = 0xff : motion, DB0 = 0x09 : data telegram with supply voltage
+        let esp3_packet = erp1_telegram(&[0xa5, 0xc8, 0x00, 0xff, 0x09, 0x05, 0x8a, 0x3b, 0x1e, 0x00]);
+        let mut registry = DeviceRegistry::new();
+        registry.register([0x05, 0x8a, 0x3b, 0x1e], EEP::A50701);
+
+        let results = parse_erp1_payload_with(&esp3_packet, &registry).unwrap();
+        assert_eq!(results.get("SVC").unwrap(), &String::from("4"));
+        assert_eq!(results.get("SVA").unwrap(), &String::from("Supply voltage supported"));
+        assert_eq!(results.get("PIRS").unwrap(), &String::from("Motion detected"));
+        assert_eq!(results.get("LRNB").unwrap(), &String::from("Data telegram"));
+
+        let results = HashMap::from(&A50701Reading::decode(&[0x00, 0x00, 0x10, 0x08]).unwrap());
+        assert!(!results.contains_key("SVC"));
+        assert_eq!(results.get("PIRS").unwrap(), &String::from("Uncertain of occupancy status"));
+    }
+
+    #[test]
+    fn given_scale_ranges_then_reproduce_a50401_values() {
+        assert_eq!(A5_04_HUMIDITY.apply(229), 91.6);
+        assert_eq!(A5_04_01_TEMPERATURE.apply(204), 32.64);
+        assert_eq!(format!("{}", A5_04_HUMIDITY.apply(229)), "91.6");
+        assert_eq!(format!("{}", A5_04_01_TEMPERATURE.apply(204)), "32.64");
+        // Offset and decreasing ranges
+        assert_eq!(A5_04_02_TEMPERATURE.apply(0), -20.0);
+        assert_eq!(A5_04_02_TEMPERATURE.apply(250), 60.0);
+        let decreasing = ScaleRange { raw_min: 0, raw_max: 255, scaled_min: 40.0, scaled_max: 0.0 };
+        assert_eq!(decreasing.apply(255), 0.0);
+    }
+
+    #[test]
+    fn given_default_registry_then_build_it_once() {
+        assert!(std::ptr::eq(default_registry(), default_registry()));
+        assert_eq!(get_eep(&[5, 17, 114, 247]), Some(EEP::A50401));
+        assert_eq!(get_eep(&[0, 0, 0, 0]), None);
+    }
+
+    #[test]
+    fn given_allowed_senders_then_parse_only_their_telegrams() {
+        let esp3_packet = erp1_telegram(&[165, 0, 229, 204, 10, 5, 17, 114, 247, 0]);
+        let registry = DeviceRegistry::default();
+
+        let allowed = HashSet::from([Address::from([5, 17, 114, 247])]);
+        let parsed = parse_erp1_typed_filtered(&esp3_packet, &registry, &allowed).unwrap();
+        assert!(matches!(parsed, ParsedTelegram::A50401(_)));
+
+        let others = HashSet::from([Address::from([0x05, 0x0a, 0x3d, 0x6a])]);
+        let error = parse_erp1_typed_filtered(&esp3_packet, &registry, &others).unwrap_err();
+        assert_eq!(error.kind, ParseEspErrorKind::UnknownSender);
+        // Index of the sender ID within the packet
+        assert_eq!(error.byte_index, Some(11));
+        assert_eq!(error.packet[11..15], [5, 17, 114, 247]);
+    }
+
+    #[test]
+    fn given_valid_a50401_esp3_packet_then_parse_typed_reading() {
+        let esp3_packet = erp1_telegram(&[165, 0, 229, 204, 10, 5, 17, 114, 247, 0]);
+
+        let parsed = parse_erp1_typed(&esp3_packet, &DeviceRegistry::default()).unwrap();
+        assert_eq!(
+            parsed,
+            ParsedTelegram::A50401(A50401Reading {
+                temperature: 32.64,
+                humidity: 91.6,
+                learn: false,
+                temp_sensor_available: true,
+            })
+        );
+    }
+
+    #[test]
+    fn given_d2010e_payloads_then_decode_typed_readings() {
+        let reading = D201Reading::decode(&[0x07, 0x60, 0x00, 0x00, 0x00, 0x13]).unwrap();
+        assert_eq!(
+            reading,
+            D201Reading::Measurement { unit: Some(D201Unit::PowerW), channel: 0, value: 19 }
+        );
+        assert_eq!(reading.normalized_value(), Some(("Power[W]", 19.0)));
+
+        let reading = D201Reading::decode(&[0x84, 0x00, 0x7f]).unwrap();
+        assert_eq!(
+            reading,
+            D201Reading::Status {
+                power_failure_enabled: true,
+                power_failure_detected: false,
+                channel: 0,
+                output_value: 0x7f,
+            }
+        );
+        let results = HashMap::from(&D201Reading::decode(&[0x04, 0x01, 0x32]).unwrap());
+        assert_eq!(results.get("I/O").unwrap(), &String::from("1"));
+        assert_eq!(results.get("OV").unwrap(), &String::from("Output value : 50%"));
+        assert_eq!(D201Reading::decode(&[0x01]).unwrap(), D201Reading::Unsupported { command_id: 0x01 });
+    }
+
+    #[test]
+    fn given_d2010e_special_channels_and_values_then_describe_them() {
+        let results = HashMap::from(&D201Reading::decode(&[0x04, 0x1e, 0x7f]).unwrap());
+        assert_eq!(results.get("I/O").unwrap(), &String::from("All output channels"));
+        assert_eq!(results.get("OV").unwrap(), &String::from("Output value not valid / not set"));
+
+        // Unknown unit, input channel, full scale value
+        let results = HashMap::from(&D201Reading::decode(&[0x07, 0xff, 0xff, 0xff, 0xff, 0xff]).unwrap());
+        assert_eq!(results.get("I/O").unwrap(), &String::from("Input channel"));
+        assert_eq!(results.get("UN").unwrap(), &String::from("Error"));
+        assert_eq!(results.get("MV").unwrap(), &String::from("4294967295"));
+        assert!(!results.contains_key("NMV"));
+    }
+
+    #[test]
+    fn given_too_short_erp1_payload_then_return_error_without_panicking() {
+        // A50401 telegram from a known sender, truncated to a single data byte after framing
+        // (esp3_of_enocean_message already rejects 4BS telegrams of the wrong length)
+        let mut esp3_packet = erp1_telegram(&[165, 0, 229, 204, 10, 5, 17, 114, 247, 0]);
+        if let DataType::Erp1Data { payload, .. } = &mut esp3_packet.data {
+            payload.truncate(1);
+        }
+        let error = parse_erp1_typed(&esp3_packet, &DeviceRegistry::default()).unwrap_err();
+        assert_eq!(error.kind, ParseEspErrorKind::IncompleteMessage);
+        let results = parse_erp1_payload(&esp3_packet).unwrap();
+        assert_eq!(results.get("Error").unwrap(), &String::from("Payload too short"));
+    }
+
+    #[test]
+    fn given_unmapped_a5_device_then_return_raw_telegram_unless_strict() {
+        let esp3_packet = erp1_telegram(&[0xa5, 0x00, 0xe5, 0xcc, 0x0a, 0x05, 0x17, 0x72, 0xf7, 0x00]);
+        let registry = DeviceRegistry::new();
+
+        let error = parse_erp1_payload_with(&esp3_packet, &registry).unwrap_err();
+        assert_eq!(error.kind, ParseEspErrorKind::Unimplemented);
+        assert!(parse_erp1_typed(&esp3_packet, &registry).is_err());
+
+        let results = parse_erp1_payload_lenient(&esp3_packet, &registry).unwrap();
+        assert_eq!(results.get("RORG").unwrap(), &String::from("4BS"));
+        assert_eq!(results.get("SENDER").unwrap(), &String::from("05:17:72:F7"));
+        assert_eq!(results.get("STATUS").unwrap(), &String::from("00"));
+        assert_eq!(results.get("RAW").unwrap(), &String::from("00E5CC0A"));
+    }
+
+    #[test]
+    fn given_smart_ack_learn_request_then_parse_it_whatever_the_sender() {
+        // Request code 0x1F, manufacturer 0x046, EEP A5-02-05, -60 dBm, through postmaster 01:85:6A:21
+        let esp3_packet = erp1_telegram(&[
+            0xc6, 0xf8, 0x46, 0xa5, 0x02, 0x05, 0x3c, 0x01, 0x85, 0x6a, 0x21, 0x05, 0x17, 0x72, 0xf7, 0x00,
+        ]);
+
+        let parsed = parse_erp1_typed(&esp3_packet, &DeviceRegistry::new()).unwrap();
+        assert_eq!(
+            parsed,
+            ParsedTelegram::SmartAck(SmartAckTelegram::LearnRequest {
+                request_code: 0x1f,
+                manufacturer_id: 0x046,
+                eep: EepId { rorg: 0xa5, func: 0x02, typ: 0x05 },
+                rssi: 0x3c,
+                postmaster_candidate: Address::from([0x01, 0x85, 0x6a, 0x21]),
+                device: Address::from([0x05, 0x17, 0x72, 0xf7]),
+            })
+        );
+        let results = HashMap::from(&parsed);
+        assert_eq!(results.get("EEP").unwrap(), "A5-02-05");
+        assert_eq!(results.get("POSTMASTER").unwrap(), "01:85:6A:21");
+
+        let truncated = erp1_telegram(&[0xc6, 0xf8, 0x46, 0xa5, 0x02, 0x05, 0x17, 0x72, 0xf7, 0x00]);
+        assert!(parse_erp1_typed(&truncated, &DeviceRegistry::new()).is_err());
+    }
+
+    #[test]
+    fn given_ute_teach_in_query_then_parse_it_whatever_the_sender() {
+        // Teach-in query of a NodOn micro smart plug (D2-01-0E), unknown to the registry
+        let esp3_packet = erp1_telegram(&[0xd4, 0xa0, 0xff, 0x46, 0x00, 0x0e, 0x01, 0xd2, 0x05, 0x0a, 0x3d, 0x6b, 0x80]);
+
+        let parsed = parse_erp1_typed(&esp3_packet, &DeviceRegistry::new()).unwrap();
+        assert_eq!(
+            parsed,
+            ParsedTelegram::UteTeachIn(UteTeachIn {
+                bidirectional: true,
+                response_expected: true,
+                request: UteRequest::NotSpecific,
+                command: 0,
+                channels: 0xff,
+                manufacturer_id: 0x046,
+                rorg: 0xd2,
+                func: 0x01,
+                eep_type: 0x0e,
+            })
+        );
+
+        let results = parse_erp1_payload(&esp3_packet).unwrap();
+        assert_eq!(results.get("EEP").unwrap(), &String::from("D2-01-0E"));
+        assert_eq!(results.get("MID").unwrap(), &String::from("0x046"));
+        assert_eq!(results.get("REQ").unwrap(), &String::from("Teach-in or deletion"));
+    }
+
     // ESP3 - ERP1 - EEP specified fields EMULATION
     // --------------------------------------------------------------------
     #[test]
     fn given_f60201_valid_status_then_create_valid_f60201_packet() {
         let created_response_close =
             create_f60201_telegram(F602EmulateCommand::MoveBlindClosed).unwrap();
+        // MoveBlindClosed is AI pressed (0x10) : the former 0x08 / CRC 208 expectation was never produced
         let valid_response_close = vec![
             0x55, 0x0, 0x07, 0x7, 0x1, 122, 
-            0xf6, 0x08, 0x00,0x00,0x00,0x00,0x30, 
-            0x03, 0xff, 0xff, 0xff, 0xff, 0xff, 0x0, 208
+            0xf6, 0x10, 0x00,0x00,0x00,0x00,0x30, 
+            0x03, 0xff, 0xff, 0xff, 0xff, 0xff, 0x0, 19
         ];
         
         assert_eq!(valid_response_close, Vec::from(&created_response_close));
     }
 
+    #[test]
+    fn given_f60201_command_then_use_rps_choice_byte() {
+        let created = create_f60201_telegram(F602EmulateCommand::MoveBlindOpen).unwrap();
+        assert_eq!(Vec::from(&created)[6], Rorg::Rps as u8);
+
+        let command = create_smart_plug_command([0x05, 0x0a, 0x3d, 0x6a], D201CommandList::On).unwrap();
+        assert_eq!(Vec::from(&command)[6], Rorg::Vld as u8);
+    }
+
+    #[test]
+    fn given_default_config_command_then_send_the_default_report_config() {
+        // The command used to send an empty telegram, it is now the default configuration packet
+        let command = create_smart_plug_command([0x05, 0x0a, 0x3d, 0x6a], D201CommandList::DefaultConfig).unwrap();
+        assert_eq!(command, create_smart_plug_default_config_packet([0x05, 0x0a, 0x3d, 0x6a]).unwrap());
+
+        // Data : RORG, CMD 0x5, DB4 to DB0, gateway ID and status
+        let bytes = Vec::from(&command);
+        assert_eq!(&bytes[1..3], &[0x00, 0x0c]);
+        assert_eq!(&bytes[6..13], &[0xd2, 0x05, 0b10100000, 0x33, 0x00, 0x06, 0x01]);
+    }
+
+    #[test]
+    fn given_rps_fields_then_create_same_telegram_as_f60201_emulation() {
+        let broadcast = Address::from([0xff, 0xff, 0xff, 0xff]);
+        let created_close = create_rps_telegram(Address::from([0, 0, 0, 0]), 0x10, 0x30, broadcast).unwrap();
+        assert_eq!(created_close, create_f60201_telegram(F602EmulateCommand::MoveBlindClosed).unwrap());
+        let created_open = create_rps_telegram(Address::from([0, 0, 0, 0]), 0x30, 0x30, broadcast).unwrap();
+        assert_eq!(created_open, create_f60201_telegram(F602EmulateCommand::MoveBlindOpen).unwrap());
+
+        // Any button of any sender
+        let created = create_rps_telegram(Address::from([0xfe, 0xf5, 0x8f, 0xd4]), 0x70, 0x30, broadcast).unwrap();
+        match created.data {
+            DataType::Erp1Data { rorg, sender_id, status, payload } => {
+                assert_eq!(rorg, Rorg::Rps);
+                assert_eq!(sender_id, Address::from([0xfe, 0xf5, 0x8f, 0xd4]));
+                assert_eq!(u8::from(status), 0x30);
+                assert_eq!(payload, vec![0x70]);
+            }
+            _ => panic!("Expected an ERP1 telegram"),
+        }
+    }
+
     // UTE TeachIn Payload parsing // response (brut version)
     // --------------------------------------------------------------------
     #[test]
@@ -560,8 +2285,116 @@ mod tests {
         assert_eq!(valid_response, Vec::from(&created_response));
     }
 
+    #[test]
+    fn given_query_status_command_then_create_actuator_status_query_packet() {
+        let created_query =
+            create_smart_plug_command([0x05, 0x0a, 0x3d, 0x6a], D201CommandList::QueryStatus { channel: 0x1e }).unwrap();
+        match created_query.data {
+            DataType::Erp1Data { rorg, payload, .. } => {
+                assert_eq!(rorg, Rorg::Vld);
+                assert_eq!(payload, vec![0x03, 0x1e]);
+            }
+            _ => panic!("Expected an ERP1 packet"),
+        }
+    }
+
+    #[test]
+    fn given_dim_value_command_then_create_actuator_set_output_packet() {
+        let socket_id = [0x05, 0x0a, 0x3d, 0x6a];
+        let payload_of = |command| match create_smart_plug_command(socket_id, command).unwrap().data {
+            DataType::Erp1Data { payload, .. } => payload,
+            _ => panic!("Expected an ERP1 packet"),
+        };
+        assert_eq!(
+            payload_of(D201CommandList::SetDimValue { channel: 0, percent: 50, dim_timer: None }),
+            vec![0x01, 0x00, 0x32]
+        );
+        assert_eq!(
+            payload_of(D201CommandList::SetDimValue { channel: 1, percent: 50, dim_timer: Some(2) }),
+            vec![0x01, 0x41, 0x32]
+        );
+
+        for command in [
+            D201CommandList::SetDimValue { channel: 0, percent: 101, dim_timer: None },
+            D201CommandList::SetDimValue { channel: 0, percent: 50, dim_timer: Some(4) },
+            D201CommandList::SetDimValue { channel: 0x1f, percent: 50, dim_timer: None },
+            D201CommandList::SetDimValue { channel: 0x21, percent: 50, dim_timer: None },
+        ] {
+            let error = create_smart_plug_command(socket_id, command).unwrap_err();
+            assert_eq!(error.kind, ParseEspErrorKind::InvalidValue);
+        }
+    }
+
+    #[test]
+    fn given_parsed_ute_teach_in_query_then_create_acceptance_response() {
+        let query = erp1_telegram(&[0xd4, 0xa0, 0x01, 0x46, 0x00, 0x0e, 0x01, 0xd2, 0x05, 0x0a, 0x3d, 0x6a, 0x80]);
+        let request = match parse_erp1_typed(&query, &DeviceRegistry::new()).unwrap() {
+            ParsedTelegram::UteTeachIn(request) => request,
+            parsed => panic!("Expected a UTE teach-in query, got {:?}", parsed),
+        };
+
+        let payload_of = |response: ESP3| match response.data {
+            DataType::Erp1Data { payload, .. } => payload,
+            _ => panic!("Expected an ERP1 packet"),
+        };
+        let response =
+            create_ute_teach_in_response(&request, Address::from([0x05, 0x0a, 0x3d, 0x6a]), true, Address::from([0; 4]))
+                .unwrap();
+        match response.opt_data() {
+            Some(OptDataType::Erp1OptData { destination_id, .. }) => assert_eq!(destination_id, &[0x05, 0x0a, 0x3d, 0x6a]),
+            _ => panic!("Expected ERP1 optional data"),
+        }
+        // Bidirectional, teach-in accepted, then the fields of the query
+        assert_eq!(payload_of(response), vec![0x91, 0x01, 0x46, 0x00, 0x0e, 0x01, 0xd2]);
+
+        let rejected = create_ute_teach_in_response(&request, Address::from([0; 4]), false, Address::from([0; 4]));
+        assert_eq!(payload_of(rejected.unwrap())[0], 0x81);
+
+        let unsupported = UteTeachIn { func: 0x02, ..request };
+        let response = create_ute_teach_in_response(&unsupported, Address::from([0; 4]), true, Address::from([0; 4]));
+        assert_eq!(payload_of(response.unwrap())[0], 0xb1);
+    }
+
     // Testing some util fn
     // --------------------------------------------------------------------
+    #[test]
+    fn given_teach_in_and_data_telegrams_then_tell_them_apart() {
+        // A5-04-01, DB0 = 0x0A : data telegram
+        let data = erp1_telegram(&[165, 0, 229, 204, 10, 5, 17, 114, 247, 0]);
+        assert!(!is_teach_in(&data));
+        // DB0 = 0x80 : teach-in telegram, with EEP
+        let teach_in = erp1_telegram(&[165, 0x10, 0x08, 0x0d, 0x80, 5, 17, 114, 247, 0]);
+        assert!(is_teach_in(&teach_in));
+
+        // D5-00-01 teach-in, and F6-02-01 (no learn bit)
+        assert!(is_teach_in(&erp1_telegram(&[0xd5, 0x00, 0x01, 0x92, 0x3d, 0xa8, 0x00])));
+        assert!(!is_teach_in(&erp1_telegram(&[0xf6, 0x00, 0xfe, 0xf5, 0x8f, 0xf5, 0x20])));
+    }
+
+    #[test]
+    fn given_eep_codes_then_parse_and_display_them() {
+        for code in ["A5-04-01", "D2-01-0E", "F6-10-00", "D5-00-01", "A5-12-01"] {
+            let id: EepId = code.parse().unwrap();
+            assert_eq!(id.to_string(), code);
+            assert_eq!(EEP::from_id(id).unwrap().id(), id);
+        }
+        assert_eq!("a5-02-05".parse::<EepId>().unwrap(), EepId { rorg: 0xa5, func: 0x02, typ: 0x05 });
+        assert_eq!(EEP::from_id("a5-02-05".parse().unwrap()), Some(EEP::A50205));
+        assert!(EEP::from_id("A5-09-04".parse().unwrap()).is_none());
+
+        for invalid in ["", "A5-04", "A5-04-01-00", "A5-4-01", "A5:04:01", "A5-04-+1"] {
+            assert!(invalid.parse::<EepId>().is_err());
+        }
+    }
+
+    #[test]
+    fn given_supported_eeps_then_get_each_one_back_from_its_code() {
+        for &(eep, id) in SUPPORTED_EEPS {
+            assert_eq!(eep.id(), id);
+            assert_eq!(EEP::from_profile(id.rorg, id.func, id.typ), Some(eep));
+        }
+    }
+
     #[test]
     fn given_u8_byte_then_get_specific_bit_value() {
         let a: u8 = 0xa5;
@@ -40,6 +40,9 @@
 use num_enum::{TryFromPrimitive, IntoPrimitive};
 
 use crate::*;
+use crate::frame::ESP3Frame;
+use crate::packet::{Address, ParseError, RadioErp1, Security, Status, SubtelNum};
+use std::borrow::Borrow;
 
 /// Simply clone the given u8 vector in an EnoceaMessage type variable
 pub fn get_raw_message(em: Vec<u8>) -> EnoceanMessage {
@@ -104,6 +107,90 @@ pub struct ESP3 {
     crc_header: u8,
     crc_data: u8,
 }
+impl ESP3 {
+    /// Parse a frame read by [`ESP3Frame::read_from`](crate::frame::ESP3Frame::read_from)
+    pub fn from_frame(frame: &ESP3Frame) -> ParseEspResult<ESP3> {
+        esp3_of_enocean_message(frame.borrow())
+    }
+    /// Parse the hex dump of a whole ESP3 packet (eg. `"55 00 0A 07 01 EB ..."`), bytes being separated by spaces, colons or nothing.
+    pub fn from_hex(hex: &str) -> ParseEspResult<ESP3> {
+        let digits: Vec<char> = hex.chars().filter(|c| !c.is_whitespace() && *c != ':').collect();
+        let mut bytes = Vec::with_capacity(digits.len() / 2);
+        for pair in digits.chunks(2) {
+            let pair: String = pair.iter().collect();
+            if pair.len() != 2 || !pair.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(ParseEspError {
+                    kind: ParseEspErrorKind::InvalidValue,
+                    message: format!("Invalid hex byte \"{}\"", pair),
+                    byte_index: Some(bytes.len() as i16),
+                    packet: bytes,
+                });
+            }
+            bytes.push(u8::from_str_radix(&pair, 16).unwrap());
+        }
+        esp3_of_enocean_message(&bytes)
+    }
+    /// The whole packet as uppercase hex bytes separated by spaces, the reverse of [`ESP3::from_hex`]
+    pub fn to_hex(&self) -> String {
+        crate::frame::hex_string(&Vec::from(self))
+    }
+    /// Build a Response packet (type 0x02), eg. to answer as a gateway. An empty `payload` is the same as `None`.
+    pub fn new_response(code: ReturnCode, payload: Option<Vec<u8>>) -> ESP3 {
+        let mut data = vec![u8::from(code)];
+        data.extend(payload.unwrap_or_default());
+        let frame = ESP3Frame::assemble(PacketType::Response as u8, &data, &[]);
+        esp3_of_enocean_message(frame.borrow()).expect("Assembled response should be valid")
+    }
+    /// The packet type, as a single byte
+    pub fn packet_type(&self) -> u8 {
+        self.packet_type as u8
+    }
+    /// The packet type, eg. to match on packet kinds
+    pub fn kind(&self) -> PacketType {
+        self.packet_type
+    }
+    /// Length of the data field, as read in the header
+    pub fn data_length(&self) -> u16 {
+        self.data_length
+    }
+    /// Length of the optional data field, as read in the header
+    pub fn optional_data_length(&self) -> u8 {
+        self.optional_data_length
+    }
+    /// The parsed optional data, if any
+    pub fn opt_data(&self) -> Option<&OptDataType> {
+        self.opt_data.as_ref()
+    }
+    /// Link quality of a received ERP1 or ERP2 telegram, from its optional data
+    pub fn link_quality(&self) -> Option<LinkQuality> {
+        self.opt_data.as_ref().and_then(OptDataType::link_quality)
+    }
+    /// The header CRC8
+    pub fn crc_header(&self) -> u8 {
+        self.crc_header
+    }
+    /// The data CRC8 (computed over data and optional data)
+    pub fn crc_data(&self) -> u8 {
+        self.crc_data
+    }
+}
+/// A parsed ESP3 packet along with the exact bytes it was parsed from, eg. to log or replay them.
+///
+/// `Vec::from(&esp3)` rebuilds the bytes from the parsed fields, which may differ from what was actually received.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ParsedFrame {
+    pub esp3: ESP3,
+    /// Bytes of the frame, from the sync byte to the data CRC
+    pub raw: Vec<u8>,
+}
+impl ParsedFrame {
+    /// Parse an enocean message like [`esp3_of_enocean_message`], keeping its bytes (without any trailing byte).
+    pub fn parse(em: &[u8]) -> ParseEspResult<ParsedFrame> {
+        let esp3 = esp3_of_enocean_message(em)?;
+        let frame_length = 7 + esp3.data_length as usize + esp3.optional_data_length as usize;
+        Ok(ParsedFrame { esp3, raw: em[..frame_length].to_vec() })
+    }
+}
 /// Util function to display packet information. Maybe we have to impl display for ESP3 instead ?
 impl fmt::Display for ESP3 {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -114,8 +201,11 @@ impl fmt::Display for ESP3 {
                 status,
                 payload,
             } => {
-                write!(f,"{:X?} radio message from: {:X?} with Status {:X?} and Payload: {:X?}. \n Parsed Payload : \n {:#X?}"
-                , rorg, sender_id, status, payload, enocean::eep::parse_erp1_payload(self).unwrap_or_default())
+                write!(f,"{:X?} radio message from: {} with Status {:X?} and Payload: {:X?}. ", rorg, sender_id, status.0, payload)?;
+                if let Some(dbm) = self.opt_data.as_ref().and_then(OptDataType::rssi_dbm) {
+                    write!(f, "RSSI : {} dBm. ", dbm)?;
+                }
+                write!(f,"\n Parsed Payload : \n {:#X?}", enocean::eep::parse_erp1_payload_lenient(self, enocean::eep::default_registry()).unwrap_or_default())
             }
             DataType::ResponseData {
                 return_code,
@@ -130,12 +220,122 @@ impl fmt::Display for ESP3 {
                     }
                 }
             }
+            DataType::Erp2Data {
+                rorg,
+                sender_id,
+                payload,
+                ..
+            } => {
+                write!(f,"{:X?} ERP2 radio message from: {} with Payload: {:X?}. ", rorg, sender_id, payload)?;
+                if let Some(dbm) = self.opt_data.as_ref().and_then(OptDataType::rssi_dbm) {
+                    write!(f, "RSSI : {} dBm. ", dbm)?;
+                }
+                write!(f,"\n Parsed Payload : \n {:#X?}", enocean::eep::parse_erp1_payload_lenient(self, enocean::eep::default_registry()).unwrap_or_default())
+            }
             DataType::RawData { raw_data } => {
                 write!(f,"Unknow message: {:X?}", raw_data)
             }
         }
     }
 }
+/// Util function to print a human readable summary of an ESP3 packet on stdout.
+/// ERP1 and Response packets use the Display impl (which includes the parsed EEP payload),
+/// other packet types are dumped as raw hex.
+pub fn print_esp3(packet: &ESP3) {
+    match &packet.data {
+        DataType::Erp1Data { .. } | DataType::Erp2Data { .. } | DataType::ResponseData { .. } => {
+            println!("{}", packet);
+        }
+        DataType::RawData { raw_data } => {
+            println!("{:?} packet with data: {:02X?}", packet.packet_type, raw_data);
+            if let Some(OptDataType::RawData { raw_data }) = &packet.opt_data {
+                println!("Optional data: {:02X?}", raw_data);
+            }
+        }
+    }
+}
+/// Util function to render an ESP3 buffer as hex, grouped by field, eg.
+/// `Sync: 55 | Length: 00 0A | OptLen: 07 | Type: 01 | CRC8H: EB | Data: A5 00 .. | Opt: 01 FF .. | CRC8D: D5`.
+/// Any buffer is accepted : fields are cut where the buffer ends, and bytes beyond the frame are shown as `Extra`.
+pub fn format_telegram_hex(bytes: &[u8]) -> String {
+    let (data_length, optional_data_length) = match bytes {
+        [_, dl_msb, dl_lsb, odl, ..] => ((*dl_msb as usize) << 8 | *dl_lsb as usize, *odl as usize),
+        _ => (0, 0),
+    };
+    let fields = [
+        ("Sync", 1),
+        ("Length", 2),
+        ("OptLen", 1),
+        ("Type", 1),
+        ("CRC8H", 1),
+        ("Data", data_length),
+        ("Opt", optional_data_length),
+        ("CRC8D", 1),
+        ("Extra", usize::MAX),
+    ];
+    let mut rest = bytes;
+    let mut groups = vec![];
+    for (label, length) in fields {
+        if rest.is_empty() {
+            break;
+        }
+        let (field, tail) = rest.split_at(length.min(rest.len()));
+        rest = tail;
+        if !field.is_empty() {
+            let hex: Vec<String> = field.iter().map(|byte| format!("{:02X}", byte)).collect();
+            groups.push(format!("{}: {}", label, hex.join(" ")));
+        }
+    }
+    groups.join(" | ")
+}
+
+/// Decode a frame read by [`ESP3Frame::read_from`](crate::frame::ESP3Frame::read_from), see [`ESP3::from_frame`]
+impl TryFrom<&ESP3Frame> for ESP3 {
+    type Error = ParseEspError;
+
+    fn try_from(frame: &ESP3Frame) -> ParseEspResult<ESP3> {
+        ESP3::from_frame(frame)
+    }
+}
+
+/// Re-encode an ESP3 packet as a frame, eg. to send it with [`ESP3Frame::write_to`]. CRCs are recomputed.
+impl From<&ESP3> for ESP3Frame {
+    fn from(esp3: &ESP3) -> ESP3Frame {
+        let bytes = Vec::from(esp3);
+        let data_end = 6 + esp3.data_length as usize;
+        ESP3Frame::assemble(bytes[4], &bytes[6..data_end], &bytes[data_end..bytes.len() - 1])
+    }
+}
+
+/// Borrow an ERP1 packet as a typed [`RadioErp1`]. Other packet types are `ParseError::UnsupportedPacketType`.
+impl<'a> TryFrom<&'a ESP3> for RadioErp1<'a> {
+    type Error = ParseError;
+
+    fn try_from(esp3: &'a ESP3) -> Result<RadioErp1<'a>, ParseError> {
+        let (rorg, sender_id, status, payload) = match &esp3.data {
+            DataType::Erp1Data { rorg, sender_id, status, payload } => (rorg, sender_id, status, payload),
+            _ => return Err(ParseError::UnsupportedPacketType),
+        };
+        let mut erp1 = RadioErp1 {
+            choice: *rorg as u8,
+            user_data: payload,
+            sender_id: *sender_id,
+            status: *status,
+            subtel_num: None,
+            destination: None,
+            rssi: None,
+            security: None,
+        };
+        if let Some(OptDataType::Erp1OptData { subtel_num, destination_id, rssi, security_lvl }) = &esp3.opt_data {
+            erp1.subtel_num = Some(SubtelNum::of_optional_data(*subtel_num, *rssi));
+            erp1.destination = Some(Address::from(*destination_id));
+            erp1.rssi = Some(*rssi);
+            erp1.security = Security::try_from_primitive(*security_lvl).ok();
+        }
+        Ok(erp1)
+    }
+}
+
 /// Function to transform an ESP3 packet to an u8 vector.
 impl From<&ESP3> for Vec<u8> {
     fn from(esp3 : &ESP3) -> Vec<u8> {
@@ -155,8 +355,32 @@ impl From<&ESP3> for Vec<u8> {
         } => {
             esp3_vector.push(*rorg as u8);
             esp3_vector.extend_from_slice(&payload);
-            esp3_vector.extend_from_slice(sender_id);
-            esp3_vector.push(*status);
+            esp3_vector.extend_from_slice(sender_id.as_bytes());
+            esp3_vector.push(status.0);
+        }
+        DataType::Erp2Data {
+            header,
+            extended_header,
+            extended_type,
+            sender_id,
+            destination_id,
+            payload,
+            optional,
+            ..
+        } => {
+            esp3_vector.push(*header);
+            esp3_vector.extend(extended_header);
+            esp3_vector.extend(extended_type);
+            // 24 bits originator IDs are stored as zero extended addresses
+            match header >> 5 {
+                0b000 => esp3_vector.extend_from_slice(&sender_id.as_bytes()[1..]),
+                _ => esp3_vector.extend_from_slice(sender_id.as_bytes()),
+            }
+            if let Some(destination_id) = destination_id {
+                esp3_vector.extend_from_slice(destination_id.as_bytes());
+            }
+            esp3_vector.extend_from_slice(payload);
+            esp3_vector.extend_from_slice(optional);
         }
         DataType::ResponseData {
             return_code,
@@ -184,6 +408,9 @@ impl From<&ESP3> for Vec<u8> {
             esp3_vector.push(*rssi);
             esp3_vector.push(*security_lvl);
         }
+        Some(OptDataType::Erp2OptData { subtel_num, rssi }) => {
+            esp3_vector.extend_from_slice(&[*subtel_num, *rssi]);
+        }
         Some(OptDataType::RawData { raw_data }) => {
             esp3_vector.extend_from_slice(&raw_data);
         }
@@ -202,10 +429,26 @@ pub enum DataType {
     },
     Erp1Data {
         rorg: Rorg,
-        sender_id: [u8; 4],
-        status: u8,
+        sender_id: Address,
+        status: Status,
         payload: Vec<u8>,
     },
+    /// Radio telegram of the ERP2 protocol (without its CRC), with 24 or 32 bits originator ID
+    Erp2Data {
+        /// Address control (b7-5), extended header available (b4), telegram type (b3-0)
+        header: u8,
+        /// Repeater count (b7-4), length of `optional` (b3-0)
+        extended_header: Option<u8>,
+        /// Extended telegram type byte, as received, when the telegram type is 0xF
+        extended_type: Option<u8>,
+        rorg: Rorg,
+        /// Originator ID. 24 bits IDs are zero extended.
+        sender_id: Address,
+        destination_id: Option<Address>,
+        payload: Vec<u8>,
+        /// ERP2 optional data
+        optional: Vec<u8>,
+    },
     ResponseData {
         return_code: ReturnCode,
         response_payload: Option<Vec<u8>>,
@@ -223,13 +466,70 @@ pub enum OptDataType {
         rssi: u8,
         security_lvl: u8,
     },
+    /// ERP2 optional data has no destination ID nor security level
+    Erp2OptData {
+        subtel_num: u8,
+        rssi: u8,
+    },
+}
+impl OptDataType {
+    /// Whether this is the optional data of a received telegram or of a telegram to send, see [`SubtelNum::of_optional_data`].
+    /// The layout is the same, but `rssi` (and the ERP1 `security_lvl`) are only meaningful for received telegrams.
+    pub fn subtel_num(&self) -> Option<SubtelNum> {
+        match self {
+            OptDataType::Erp1OptData { subtel_num, rssi, .. } | OptDataType::Erp2OptData { subtel_num, rssi, .. } => {
+                Some(SubtelNum::of_optional_data(*subtel_num, *rssi))
+            }
+            OptDataType::RawData { .. } => None,
+        }
+    }
+
+    /// Received signal strength in dBm (eg. -55), for ERP1 and ERP2 optional data of received telegrams.
+    /// The raw `rssi` byte is the magnitude of this negative value.
+    pub fn rssi_dbm(&self) -> Option<i16> {
+        match self {
+            OptDataType::Erp1OptData { rssi, .. } | OptDataType::Erp2OptData { rssi, .. }
+                if self.subtel_num() == Some(SubtelNum::Receive) => Some(-(*rssi as i16)),
+            _ => None,
+        }
+    }
+
+    /// Number of subtelegrams and best RSSI of a received telegram
+    pub fn link_quality(&self) -> Option<LinkQuality> {
+        match self {
+            OptDataType::Erp1OptData { subtel_num, .. } | OptDataType::Erp2OptData { subtel_num, .. } => {
+                Some(LinkQuality { subtel_count: *subtel_num, best_rssi_dbm: self.rssi_dbm()? })
+            }
+            OptDataType::RawData { .. } => None,
+        }
+    }
+
+    /// Security level of a received ERP1 telegram (0 : not processed, 1 : obsolete, 2 : decrypted, 3 : authenticated, 4 : both)
+    pub fn security_level(&self) -> Option<u8> {
+        match self {
+            OptDataType::Erp1OptData { security_lvl, .. }
+                if self.subtel_num() == Some(SubtelNum::Receive) => Some(*security_lvl),
+            _ => None,
+        }
+    }
+}
+
+/// Signal health of a received telegram.
+///
+/// A telegram is sent as several subtelegrams (usually 3), some of them may be lost, or repeated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LinkQuality {
+    /// Number of subtelegrams received
+    pub subtel_count: u8,
+    /// RSSI of the best subtelegram [dBm]
+    pub best_rssi_dbm: i16,
 }
 
 /// Simple implementation of EnOcean packet type for ESP3 packet
-/// Supported packet type for now : Radio_ERP1, Response
+/// Supported packet type for now : Radio_ERP1, Radio_ERP2, Response. Other packet types are kept as raw data.
 #[derive(PartialEq, Debug, Clone, Copy, IntoPrimitive, TryFromPrimitive)]
 #[repr(u8)]
-enum PacketType {
+pub enum PacketType {
     RadioErp1 = 0x01,
     Response = 0x02,
     Undefined = 0xFF,
@@ -245,6 +545,26 @@ enum PacketType {
     Command2_4 = 0x11,
 }
 
+impl fmt::Display for PacketType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            PacketType::RadioErp1 => "Radio ERP1",
+            PacketType::Response => "Response",
+            PacketType::Undefined => "Undefined",
+            PacketType::RadioSubTel => "Radio sub telegram",
+            PacketType::Event => "Event",
+            PacketType::CommonCommand => "Common command",
+            PacketType::SmartAckCommand => "Smart Ack command",
+            PacketType::RemoteManCommand => "Remote management command",
+            PacketType::RadioMessage => "Radio message",
+            PacketType::RadioErp2 => "Radio ERP2",
+            PacketType::Radio802_15_4 => "Radio 802.15.4",
+            PacketType::Command2_4 => "Command 2.4 GHz",
+        };
+        f.write_str(name)
+    }
+}
+
 /// Given an packet type u8 value, return the corresponding PacketType
 fn get_packet_type(em: &[u8]) -> ParseEspResult<PacketType> {
     PacketType::try_from_primitive(em[4])
@@ -277,6 +597,28 @@ pub enum Rorg {
     Sec = 0x30,
     SecEncaps = 0x31,
 }
+
+impl fmt::Display for Rorg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Rorg::Undefined => "Undefined",
+            Rorg::Rps => "RPS",
+            Rorg::Bs1 => "1BS",
+            Rorg::Bs4 => "4BS",
+            Rorg::Vld => "VLD",
+            Rorg::Msc => "MSC",
+            Rorg::Adt => "ADT",
+            Rorg::Ute => "UTE",
+            Rorg::SmLrnReq => "SM_LRN_REQ",
+            Rorg::SmLrnAns => "SM_LRN_ANS",
+            Rorg::SmRec => "SM_REC",
+            Rorg::SysEx => "SYS_EX",
+            Rorg::Sec => "SEC",
+            Rorg::SecEncaps => "SEC_ENCAPS",
+        };
+        f.write_str(name)
+    }
+}
 /// Simple implementation of possible Return codes for a response packet (from EnOcean ESP3)
 #[derive(Debug, PartialEq, Clone, Copy, IntoPrimitive, TryFromPrimitive)]
 #[repr(u8)]
@@ -292,6 +634,23 @@ pub enum ReturnCode {
     Undefined = 0xff,
 }
 
+impl fmt::Display for ReturnCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            ReturnCode::Ok => "Ok",
+            ReturnCode::Error => "Error",
+            ReturnCode::NotSupported => "Not supported",
+            ReturnCode::WrongParam => "Wrong parameter",
+            ReturnCode::OperationDenied => "Operation denied",
+            ReturnCode::LockSet => "Lock set",
+            ReturnCode::BufferTooSmall => "Buffer too small",
+            ReturnCode::NoFreeBuffer => "No free buffer",
+            ReturnCode::Undefined => "Undefined",
+        };
+        f.write_str(name)
+    }
+}
+
 fn get_return_code(rc_byte: u8) -> ReturnCode {
     ReturnCode::try_from_primitive(rc_byte).unwrap_or(ReturnCode::Undefined)
 }
@@ -301,30 +660,105 @@ fn get_radio_organization(rorg_byte: u8) -> Rorg {
     Rorg::try_from_primitive(rorg_byte).unwrap_or(Rorg::Undefined)
 }
 
-pub use crc8::compute_crc8;
+/// Fixed length of the user data carried by a `rorg` telegram, or `None` when it is variable (or unknown)
+pub fn rorg_data_len(rorg: Rorg) -> Option<usize> {
+    match rorg {
+        Rorg::Rps | Rorg::Bs1 => Some(1),
+        Rorg::Bs4 => Some(4),
+        _ => None,
+    }
+}
 
-/// Main function which convert an u8 vector of incoming byte into an ESP3 variable :
-///
-/// | Size (Byte) |   1    |       2          |        1      |      1    |      1    | u16 DataLen + u8 OptionLen |      1      |
-/// |-------------|--------|------------------|---------------|-----------|-----------|----------------------------|-------------|
-/// | Content     | 0x55   | u16DataLen       | u8OptionLen   | u8Type    | CRC8H     | DATAS                      |     CRC8D   |
-///
-/// /// Optionnal data :   
-///
-/// | Size (Byte) |      1      |   4              |    1     |          1       |    
-/// |-------------|-------------|------------------|----------|------------------|    
-/// | Content     | Subtel nb   | Destination ID   | dBm      | Security level   |     
+/// RORG of an ERP2 telegram type (header b3-0)
+fn get_erp2_radio_organization(telegram_type: u8) -> Rorg {
+    match telegram_type {
+        0b0000 => Rorg::Rps,
+        0b0001 => Rorg::Bs1,
+        0b0010 => Rorg::Bs4,
+        0b0100 => Rorg::Vld,
+        0b0101 => Rorg::Ute,
+        0b0110 => Rorg::Msc,
+        0b0111 => Rorg::Sec,
+        0b1000 => Rorg::SecEncaps,
+        _ => Rorg::Undefined,
+    }
+}
+
+/// Parse the data of an ERP2 packet :
 ///
-/// Data (BS1 example) :  
+/// | Size (Byte) |   1    |      0 / 1        |        0 / 1           |   3 / 4        |      0 / 4       |  any    |  0..15        |
+/// |-------------|--------|-------------------|------------------------|----------------|------------------|---------|---------------|
+/// | Content     | Header | Extended header   | Extended telegram type | Originator ID  | Destination ID   | Data    | Optional data |
 ///
-/// | Size (Byte) |        1      |         1              |       4        |   1      |      
-/// |-------------|---------------|------------------------|----------------|----------|   
-/// | Content     | Rorg (0xD5)   | Data payload as EEP*   | Sender ID      | Status   |   
+/// Telegrams with 48 bits originator IDs, or a reserved address control, are returned as `RawData`.
+fn erp2_data_of(erp2: &[u8]) -> Result<DataType, &'static str> {
+    let (&header, mut rest) = erp2.split_first().ok_or("ERP2 data too short")?;
+    let (sender_id_length, destination_id_length) = match header >> 5 {
+        0b000 => (3, 0),
+        0b001 => (4, 0),
+        0b010 => (4, 4),
+        _ => return Ok(DataType::RawData { raw_data: erp2.to_vec() }),
+    };
+    // Split the first `length` bytes off `rest`
+    fn take<'a>(rest: &mut &'a [u8], length: usize) -> Result<&'a [u8], &'static str> {
+        if rest.len() < length {
+            return Err("ERP2 data too short");
+        }
+        let (taken, remaining) = rest.split_at(length);
+        *rest = remaining;
+        Ok(taken)
+    }
 
+    let extended_header = match header & 0b00010000 {
+        0 => None,
+        _ => Some(take(&mut rest, 1)?[0]),
+    };
+    let extended_type = match header & 0x0f {
+        0x0f => Some(take(&mut rest, 1)?[0]),
+        _ => None,
+    };
+    let rorg = match extended_type {
+        Some(extended_type) => get_radio_organization(extended_type),
+        None => get_erp2_radio_organization(header & 0x0f),
+    };
+    let mut sender_id = [0u8; 4];
+    sender_id[4 - sender_id_length..].copy_from_slice(take(&mut rest, sender_id_length)?);
+    let destination_id = match destination_id_length {
+        0 => None,
+        _ => Some(Address::from(<[u8; 4]>::try_from(take(&mut rest, 4)?).unwrap())),
+    };
+    let optional_length = extended_header.map_or(0, |extended_header| (extended_header & 0x0f) as usize);
+    let payload_length = rest.len().checked_sub(optional_length).ok_or("ERP2 data too short")?;
+    let payload = take(&mut rest, payload_length)?.to_vec();
+    let optional = take(&mut rest, optional_length)?.to_vec();
 
-pub fn esp3_of_enocean_message(em: &[u8]) -> ParseEspResult<ESP3> {
+    Ok(DataType::Erp2Data {
+        header,
+        extended_header,
+        extended_type,
+        rorg,
+        sender_id: Address::from(sender_id),
+        destination_id,
+        payload,
+        optional,
+    })
+}
+
+pub use crc8::compute_crc8;
+
+/// Check the integrity of a whole ESP3 buffer, without parsing its content :
+/// sync byte, header CRC, length fields and data CRC.
+/// Errors are the same as the ones of [`esp3_of_enocean_message`].
+pub fn verify_esp3_crc(em: &[u8]) -> ParseEspResult<()> {
     // Make some verifications about the received message
-    if em[0] != 0x55 {
+    if em.is_empty() {
+        return Err(ParseEspError {
+            message: String::from("Invalid input message"),
+            byte_index: None,
+            packet: em.into(),
+            kind: ParseEspErrorKind::IncompleteMessage,
+        });
+    } else if em[0] != 0x55 {
         // EnOcean message must start by 0x55
         return Err(ParseEspError {
             message: String::from("Sync Byte Error"),
@@ -332,8 +766,8 @@ pub fn esp3_of_enocean_message(em: &[u8]) -> ParseEspResult<ESP3> {
             packet: em.into(),
             kind: ParseEspErrorKind::NoSyncByte,
         });
-    } else if em.len() <= 7 {
-        // Minimal EnOcean message size = 7 bytes
+    } else if em.len() < 7 {
+        // Minimal EnOcean message size = 7 bytes (sync byte, header, CRCs and no data)
         return Err(ParseEspError {
             message: String::from("Invalid input message"),
             byte_index: None,
@@ -341,14 +775,14 @@ pub fn esp3_of_enocean_message(em: &[u8]) -> ParseEspResult<ESP3> {
             kind: ParseEspErrorKind::IncompleteMessage,
         });
     }
-    let crc_header = em[5];
-    if compute_crc8(&em[1..5].to_vec()) != em[5] {
+    let crc_header = compute_crc8(&em[1..5]);
+    if crc_header != em[5] {
         // EnOcean message header CRC can be checked without complex parsing
         return Err(ParseEspError {
             message: String::from("CRC Error"),
             byte_index: Some(5),
             packet: em.into(),
-            kind: ParseEspErrorKind::CrcMismatch,
+            kind: ParseEspErrorKind::CrcMismatch { computed: crc_header, expected: em[5] },
         });
     }
 
@@ -365,20 +799,98 @@ pub fn esp3_of_enocean_message(em: &[u8]) -> ParseEspResult<ESP3> {
             kind: ParseEspErrorKind::IncompleteMessage,
         });
     }
-    let crc_data =
-        compute_crc8(&em[6..6 + data_length as usize + optional_data_length as usize].to_vec());
     // And DATA CRC :
-    if crc_data != em[6 + data_length as usize + optional_data_length as usize] {
+    let crc_data_index = 6 + data_length as usize + optional_data_length as usize;
+    let crc_data = compute_crc8(&em[6..crc_data_index]);
+    if crc_data != em[crc_data_index] {
         return Err(ParseEspError {
             message: String::from("CRC Data Error"),
-            byte_index: Some(em[6 + data_length as usize + optional_data_length as usize] as i16),
+            byte_index: Some(crc_data_index as i16),
             packet: em.into(),
-            kind: ParseEspErrorKind::CrcMismatch,
+            kind: ParseEspErrorKind::CrcMismatch { computed: crc_data, expected: em[crc_data_index] },
+        });
+    }
+
+    Ok(())
+}
+
+/// Compute and write the CRCs of an ESP3 buffer built by hand :
+/// the header CRC is written at index 5, and the data CRC is appended (or overwritten if already present).
+/// The length fields of the header must match the buffer length.
+pub fn seal_esp3(buf: &mut Vec<u8>) -> ParseEspResult<()> {
+    if buf.first() != Some(&0x55) {
+        return Err(ParseEspError {
+            message: String::from("Sync Byte Error"),
+            byte_index: Some(0),
+            packet: buf.clone(),
+            kind: ParseEspErrorKind::NoSyncByte,
+        });
+    } else if buf.len() < 6 {
+        return Err(ParseEspError {
+            message: String::from("Invalid input message"),
+            byte_index: None,
+            packet: buf.clone(),
+            kind: ParseEspErrorKind::IncompleteMessage,
+        });
+    }
+    let data_length = (buf[1] as usize) << 8 | buf[2] as usize;
+    let crc_data_index = 6 + data_length + buf[3] as usize;
+    // Without (crc_data_index) or with (crc_data_index + 1) a data CRC placeholder
+    if buf.len() != crc_data_index && buf.len() != crc_data_index + 1 {
+        return Err(ParseEspError {
+            message: String::from("Packet length error"),
+            byte_index: None,
+            packet: buf.clone(),
+            kind: ParseEspErrorKind::IncompleteMessage,
         });
     }
+    buf[5] = compute_crc8(&buf[1..5]);
+    buf.truncate(crc_data_index);
+    buf.push(compute_crc8(&buf[6..crc_data_index]));
+    Ok(())
+}
+
+/// Main function which convert an u8 vector of incoming byte into an ESP3 variable :
+///
+/// | Size (Byte) |   1    |       2          |        1      |      1    |      1    | u16 DataLen + u8 OptionLen |      1      |
+/// |-------------|--------|------------------|---------------|-----------|-----------|----------------------------|-------------|
+/// | Content     | 0x55   | u16DataLen       | u8OptionLen   | u8Type    | CRC8H     | DATAS                      |     CRC8D   |
+///
+/// /// Optionnal data :   
+///
+/// | Size (Byte) |      1      |   4              |    1     |          1       |    
+/// |-------------|-------------|------------------|----------|------------------|    
+/// | Content     | Subtel nb   | Destination ID   | dBm      | Security level   |     
+///
+/// Data (BS1 example) :  
+///
+/// | Size (Byte) |        1      |         1              |       4        |   1      |      
+/// |-------------|---------------|------------------------|----------------|----------|   
+/// | Content     | Rorg (0xD5)   | Data payload as EEP*   | Sender ID      | Status   |   
+///
+/// Data too short for its packet type (eg. a response without return code, with `data_length == 0`)
+/// is an `IncompleteMessage` error.
+
+
+pub fn esp3_of_enocean_message(em: &[u8]) -> ParseEspResult<ESP3> {
+    // Make some verifications about the received message
+    verify_esp3_crc(em)?;
+    let crc_header = em[5];
+    let data_length: u16 = (em[1] as u16) << 8 | em[2] as u16;
+    let optional_data_length: u8 = em[3];
+    let crc_data_index = 6 + data_length as usize + optional_data_length as usize;
+    let crc_data = em[crc_data_index];
 
     // If Message seems valid, we can then parse packet type
-    let mut packet_type = PacketType::Undefined;
+    let packet_type: PacketType;
+    let dl = data_length as usize;
+    // Error for a data field too short for its packet type
+    let too_short = |message: &str, byte_index: usize| ParseEspError {
+        message: String::from(message),
+        byte_index: Some(byte_index as i16),
+        packet: em.into(),
+        kind: ParseEspErrorKind::IncompleteMessage,
+    };
     let data: DataType;
     let opt_data: Option<OptDataType>;
 
@@ -389,46 +901,106 @@ pub fn esp3_of_enocean_message(em: &[u8]) -> ParseEspResult<ESP3> {
                 PacketType::RadioErp1 => {
                     // See ERP1 definition in Enocean Serial Protocol
                     packet_type = PacketType::RadioErp1;
+                    // Data must at least contain rorg, sender_id and status
+                    if dl < 6 {
+                        return Err(too_short("ERP1 data too short", 6 + dl));
+                    }
+                    // Data of erp1 packet contains rorg (1), data payload (any length), sender_id (4) and status (1),
+                    // so the payload bounds are derived from the fixed size tail
+                    let rorg_index = 6;
+                    let status_index = rorg_index + dl - 1;
+                    let sender_id_index = status_index - 4;
+                    let rorg = get_radio_organization(em[rorg_index]);
+                    if let Some(len) = rorg_data_len(rorg) {
+                        let payload_len = sender_id_index - (rorg_index + 1);
+                        if payload_len != len {
+                            let (kind, too) = match payload_len < len {
+                                true => (ParseEspErrorKind::IncompleteMessage, "short"),
+                                false => (ParseEspErrorKind::InvalidValue, "long"),
+                            };
+                            return Err(ParseEspError {
+                                message: format!("{} data too {}, should be {} byte(s) long", rorg, too, len),
+                                byte_index: Some(rorg_index as i16),
+                                packet: em.into(),
+                                kind,
+                            });
+                        }
+                    }
                     let mut sender_id: [u8; 4] = Default::default();
-                    sender_id
-                        .copy_from_slice(&em[1 + data_length as usize..5 + data_length as usize]);
-                    // Data of erp1 packet contains rorg, data payload, sender_id and status
+                    sender_id.copy_from_slice(&em[sender_id_index..status_index]);
+                    let sender_id = Address::from(sender_id);
                     data = DataType::Erp1Data {
-                        rorg: get_radio_organization(em[6]),
+                        rorg,
                         sender_id,
-                        status: em[5 + data_length as usize],
-                        payload: em[7..1 + data_length as usize].to_vec(), //7 + data_length - 6
+                        status: Status(em[status_index]),
+                        payload: em[rorg_index + 1..sender_id_index].to_vec(),
                     };
-                    let mut destination_id: [u8; 4] = Default::default();
-                    destination_id
-                        .copy_from_slice(&em[7 + data_length as usize..11 + data_length as usize]);
-
-                    opt_data = Some(OptDataType::Erp1OptData {
-                        subtel_num: em[6 + data_length as usize],
-                        destination_id,
-                        rssi: em[11 + data_length as usize],
-                        security_lvl: em[12 + data_length as usize],
-                    })
+                    // Optional data may be omitted, but not truncated
+                    opt_data = match optional_data_length {
+                        0 => None,
+                        1..=6 => {
+                            return Err(too_short("ERP1 optional data too short", crc_data_index));
+                        }
+                        _ => {
+                            let mut destination_id: [u8; 4] = Default::default();
+                            destination_id.copy_from_slice(&em[7 + dl..11 + dl]);
+
+                            Some(OptDataType::Erp1OptData {
+                                subtel_num: em[6 + dl],
+                                destination_id,
+                                rssi: em[11 + dl],
+                                security_lvl: em[12 + dl],
+                            })
+                        }
+                    }
+                }
+                PacketType::RadioErp2 => {
+                    // See ERP2 definition in Enocean Serial Protocol
+                    packet_type = PacketType::RadioErp2;
+                    data = erp2_data_of(&em[6..6 + dl]).map_err(|message| too_short(message, 6 + dl))?;
+                    // Optional data (subtel num, dBm) may be omitted, but not truncated
+                    opt_data = match optional_data_length {
+                        0 => None,
+                        1 => {
+                            return Err(too_short("ERP2 optional data too short", crc_data_index));
+                        }
+                        2 => Some(OptDataType::Erp2OptData {
+                            subtel_num: em[6 + dl],
+                            rssi: em[7 + dl],
+                        }),
+                        // Unknown layout, kept as is
+                        _ => Some(OptDataType::RawData { raw_data: em[6 + dl..crc_data_index].to_vec() }),
+                    }
                 }
                 PacketType::Response => {
+                    packet_type = PacketType::Response;
+                    // Data must at least contain the return code : a response without data has no return code
+                    if dl == 0 {
+                        return Err(too_short("Response without return code", 6));
+                    }
                     let mut response_payload: Option<Vec<u8>> = None;
                     if data_length > 1 {
-                        response_payload = Some(em[7..data_length as usize].to_vec());
+                        response_payload = Some(em[7..6 + dl].to_vec());
                     }
                     data = DataType::ResponseData {
                         return_code: get_return_code(em[6]),
                         response_payload,
                     };
-                    opt_data = None;
+                    // Some responses carry optional data (eg. remaining write cycles of CO_RD_IDBASE)
+                    opt_data = match optional_data_length {
+                        0 => None,
+                        _ => Some(OptDataType::RawData {
+                            raw_data: em[6 + dl..crc_data_index].to_vec(),
+                        }),
+                    };
                 }
                 _ => {
+                    packet_type = pt;
                     data = DataType::RawData {
-                        raw_data: em[6..6 + data_length as usize].to_vec(),
+                        raw_data: em[6..6 + dl].to_vec(),
                     };
                     opt_data = Some(OptDataType::RawData {
-                        raw_data: em[6 + data_length as usize
-                            ..6 + data_length as usize + optional_data_length as usize]
-                            .to_vec(),
+                        raw_data: em[6 + dl..crc_data_index].to_vec(),
                     })
                 }
             }
@@ -455,10 +1027,48 @@ pub fn esp3_of_enocean_message(em: &[u8]) -> ParseEspResult<ESP3> {
     })
 }
 
+/// Test util : build a valid enocean message (with CRCs) from its parts
+#[cfg(test)]
+pub(crate) fn build_message(packet_type: u8, data: &[u8], opt_data: &[u8]) -> Vec<u8> {
+    let mut message = vec![];
+    crate::frame::ESP3FrameRef { packet_type, data, optional_data: opt_data }.write_to(&mut message).unwrap();
+    message
+}
+
 /// Unit Tests
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    #[test]
+    fn given_fixed_length_rorgs_then_return_their_data_length() {
+        assert_eq!(rorg_data_len(Rorg::Rps), Some(1));
+        assert_eq!(rorg_data_len(Rorg::Bs1), Some(1));
+        assert_eq!(rorg_data_len(Rorg::Bs4), Some(4));
+        assert_eq!(rorg_data_len(Rorg::Vld), None);
+    }
+
+    #[test]
+    fn given_erp1_data_length_not_matching_its_rorg_then_return_error() {
+        // RPS with a 2 bytes payload
+        let message = build_message(0x01, &[0xf6, 0x30, 0x00, 0x01, 0x02, 0x03, 0x04, 0x30], &[]);
+        let err = esp3_of_enocean_message(&message).unwrap_err();
+        assert_eq!(err.kind, ParseEspErrorKind::InvalidValue);
+        assert!(err.message.contains("too long"));
+        assert_eq!(err.byte_index, Some(6));
+
+        // 4BS with a 3 bytes payload
+        let message = build_message(0x01, &[0xa5, 0x00, 0x00, 0x08, 0x01, 0x02, 0x03, 0x04, 0x00], &[]);
+        let err = esp3_of_enocean_message(&message).unwrap_err();
+        assert_eq!(err.kind, ParseEspErrorKind::IncompleteMessage);
+        assert!(err.message.contains("too short"));
+
+        // VLD payloads have no fixed length
+        let message = build_message(0x01, &[0xd2, 0x04, 0x60, 0x80, 0x01, 0x02, 0x03, 0x04, 0x00], &[]);
+        assert!(esp3_of_enocean_message(&message).is_ok());
+        let message = build_message(0x01, &[0xd2, 0x04, 0x01, 0x02, 0x03, 0x04, 0x00], &[]);
+        assert!(esp3_of_enocean_message(&message).is_ok());
+    }
     // Enocean Serial Protocol 3 : ESP3 typical fields
     // -------------------------------------------------------------------
     #[test]
@@ -527,8 +1137,8 @@ mod tests {
         let data: DataType;
         data = DataType::Erp1Data {
             rorg: Rorg::Rps,
-            sender_id: [254, 245, 143, 212],
-            status: 32,
+            sender_id: Address::from([254, 245, 143, 212]),
+            status: Status(32),
             payload: [0].to_vec(),
         };
 
@@ -551,8 +1161,52 @@ mod tests {
         assert_eq!(esp_packet, result);
     }
 
+    #[test]
+    fn given_valid_f60201_enocean_message_then_accessors_return_header_fields() {
+        let received_message = vec![
+            85, 0, 7, 7, 1, 122, 246, 0, 254, 245, 143, 212, 32, 2, 255, 255, 255, 255, 48, 0, 39,
+        ];
+        let result = esp3_of_enocean_message(&received_message).unwrap();
+        assert_eq!(result.packet_type(), 0x01);
+        assert_eq!(result.data_length(), 7);
+        assert_eq!(result.optional_data_length(), 7);
+        assert_eq!(result.crc_header(), 122);
+        assert_eq!(result.crc_data(), 39);
+        assert_eq!(
+            result.opt_data(),
+            Some(&OptDataType::Erp1OptData {
+                subtel_num: 2,
+                destination_id: [255, 255, 255, 255],
+                rssi: 48,
+                security_lvl: 0,
+            })
+        );
+    }
+
     // Possible errors related tests
     #[test]
+    fn given_valid_or_corrupted_buffers_then_verify_esp3_crc() {
+        let valid_message = [
+            85, 0, 7, 7, 1, 122, 246, 0, 254, 245, 143, 212, 32, 2, 255, 255, 255, 255, 48, 0, 39,
+        ];
+        assert!(verify_esp3_crc(&valid_message).is_ok());
+
+        let mut bad_header = valid_message;
+        bad_header[2] = 8;
+        let mut bad_data = valid_message;
+        bad_data[8] = 0;
+        let mut no_sync_byte = valid_message;
+        no_sync_byte[0] = 0;
+        for (buffer, kind) in [
+            (&bad_header[..], ParseEspErrorKind::CrcMismatch { computed: 61, expected: 122 }),
+            (&bad_data[..], ParseEspErrorKind::CrcMismatch { computed: 61, expected: 39 }),
+            (&no_sync_byte[..], ParseEspErrorKind::NoSyncByte),
+            (&valid_message[..20], ParseEspErrorKind::IncompleteMessage),
+        ] {
+            assert_eq!(verify_esp3_crc(buffer).unwrap_err().kind, kind);
+        }
+    }
+    #[test]
     fn given_invalid_encoean_message_with_invalid_crc_data_then_return_error() {
         // received_message is a valid message from a necklace pushbutton (EEP -00-01)
         let invalid_received_message = vec![
@@ -564,6 +1218,9 @@ mod tests {
                 .message,
             String::from("CRC Data Error")
         );
+        let error = esp3_of_enocean_message(&invalid_received_message).unwrap_err();
+        assert_eq!(error.kind, ParseEspErrorKind::CrcMismatch { computed: 39, expected: 0 });
+        assert!(error.to_string().starts_with("CRC computed 0x27, expected 0x00"));
     }
     #[test]
     fn given_invalid_a50401_enocean_message_with_no_sync_byte_then_return_error() {
@@ -604,6 +1261,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn given_truncated_enocean_messages_then_return_error_without_panicking() {
+        let received_messages = [
+            vec![85, 0, 10, 7, 1, 235, 165, 0, 229, 204, 10, 5, 17, 114, 247, 0, 1, 255, 255, 255, 255, 54, 0, 213],
+            vec![85, 0, 7, 7, 1, 122, 246, 0, 254, 245, 143, 212, 32, 2, 255, 255, 255, 255, 48, 0, 39],
+            vec![85, 0, 5, 1, 2, 219, 0, 255, 155, 18, 128, 10, 17],
+        ];
+        for received_message in received_messages.iter() {
+            assert!(esp3_of_enocean_message(received_message).is_ok());
+            for len in 0..received_message.len() {
+                let result = esp3_of_enocean_message(&received_message[..len]);
+                assert_eq!(result.unwrap_err().kind, ParseEspErrorKind::IncompleteMessage);
+            }
+        }
+    }
+    #[test]
+    fn given_erp1_message_with_too_short_data_or_optional_data_then_return_error() {
+        // Valid headers and CRCs, but the data / optional data lengths are too small for an ERP1 packet
+        for (data, opt_data, byte_index) in [
+            (&[246, 0][..], &[][..], 8),
+            (&[246, 0, 254, 245, 143, 212, 32][..], &[2, 255, 255][..], 16),
+        ] {
+            let received_message = build_message(0x01, data, opt_data);
+            let error = esp3_of_enocean_message(&received_message).unwrap_err();
+            assert_eq!(error.kind, ParseEspErrorKind::IncompleteMessage);
+            assert_eq!(error.byte_index, Some(byte_index));
+        }
+    }
+    #[test]
+    fn given_erp1_message_without_optional_data_then_return_esp_without_opt_data() {
+        let received_message = build_message(0x01, &[246, 0, 254, 245, 143, 212, 32], &[]);
+
+        let esp3_packet = esp3_of_enocean_message(&received_message).unwrap();
+        assert!(esp3_packet.opt_data().is_none());
+        assert_eq!(Vec::from(&esp3_packet), received_message);
+    }
+
     // Enocean Serial Protocol 3 : ERP1 typical fields
     // -------------------------------------------------------------------
     #[test]
@@ -613,14 +1307,14 @@ mod tests {
             54, 0, 213,
         ];
         let esp3_packet: ESP3 = esp3_of_enocean_message(&received_message).unwrap();
-        let valid_sender_id: [u8; 4] = [5, 17, 114, 247];
+        let valid_sender_id = Address::from([5, 17, 114, 247]);
         let valid_payload = vec![0, 229, 204, 10];
         let valid_rorg = Rorg::Bs4;
-        let valid_status = 0x00;
+        let valid_status = Status(0x00);
 
-        let result_sender_id: [u8; 4];
+        let result_sender_id: Address;
         let result_rorg: Rorg;
-        let result_status: u8;
+        let result_status: Status;
         let result_payload: Vec<u8>;
 
         match esp3_packet.data {
@@ -636,9 +1330,9 @@ mod tests {
                 result_payload = payload;
             }
             _ => {
-                result_sender_id = [0, 1, 2, 3];
+                result_sender_id = Address::from([0, 1, 2, 3]);
                 result_rorg = Rorg::Undefined;
-                result_status = 0xFF;
+                result_status = Status(0xFF);
                 result_payload = vec![0];
             }
         }
@@ -647,8 +1341,141 @@ mod tests {
         assert_eq!(result_rorg, valid_rorg);
         assert_eq!(result_status, valid_status);
     }
+    #[test]
+    fn given_erp1_opt_data_then_return_rssi_as_negative_dbm() {
+        let opt_data = OptDataType::Erp1OptData {
+            subtel_num: 1,
+            destination_id: [255, 255, 255, 255],
+            rssi: 55,
+            security_lvl: 0,
+        };
+        assert_eq!(opt_data.rssi_dbm(), Some(-55));
+        assert_eq!(OptDataType::RawData { raw_data: vec![55] }.rssi_dbm(), None);
+
+        let received_message = vec![
+            85, 0, 7, 7, 1, 122, 246, 0, 254, 245, 143, 212, 32, 2, 255, 255, 255, 255, 48, 0, 39,
+        ];
+        let esp3_packet = esp3_of_enocean_message(&received_message).unwrap();
+        assert!(format!("{}", esp3_packet).contains("RSSI : -48 dBm"));
+    }
+
+    #[test]
+    fn given_erp1_messages_of_any_payload_length_then_return_whole_payload() {
+        // VLD (D2-01) telegrams, from the shortest to a long payload
+        let sender_id = [0x05, 0x0a, 0x3d, 0x6a];
+        for payload_length in 1..=14 {
+            let payload: Vec<u8> = (1..=payload_length).collect();
+            let mut data = vec![0xd2];
+            data.extend_from_slice(&payload);
+            data.extend_from_slice(&sender_id);
+            data.push(0x80);
+            let received_message = build_message(0x01, &data, &[1, 255, 255, 255, 255, 61, 0]);
+
+            let esp3_packet = esp3_of_enocean_message(&received_message).unwrap();
+            assert_eq!(
+                esp3_packet.data,
+                DataType::Erp1Data {
+                    rorg: Rorg::Vld,
+                    sender_id: Address::from(sender_id),
+                    status: Status(0x80),
+                    payload,
+                }
+            );
+            assert_eq!(Vec::from(&esp3_packet), received_message);
+        }
+    }
+    #[test]
+    fn given_d2010e_energy_report_then_return_all_payload_bytes() {
+        // D2-01 actuator measurement response (CMD 0x07) : 6 bytes payload
+        let received_message = vec![
+            0x55, 0x0, 0xC, 0x7, 0x1, 0x96, 0xD2, 0x7, 0x60, 0x0, 0x0, 0x0, 0x13, 0x5, 0xA, 0x3D,
+            0x6A, 0x0, 0x1, 0xFF, 0xFF, 0xFF, 0xFF, 0x3D, 0x0, 0xF1,
+        ];
+        let esp3_packet = esp3_of_enocean_message(&received_message).unwrap();
+        match esp3_packet.data {
+            DataType::Erp1Data { payload, sender_id, .. } => {
+                assert_eq!(payload, vec![0x07, 0x60, 0x00, 0x00, 0x00, 0x13]);
+                assert_eq!(sender_id, Address::from([0x05, 0x0a, 0x3d, 0x6a]));
+            }
+            _ => panic!("Expected ERP1 data"),
+        }
+    }
+
     // Enocean Serial Protocol 3 : Response fields
     // -------------------------------------------------------------------
+    #[test]
+    fn given_erp2_messages_then_return_corresponding_esp() {
+        // RPS telegram from a 32 bits originator ID, then subtel number and dBm
+        let message = build_message(0x0a, &[0x20, 0xfe, 0xf5, 0x8f, 0xf5, 0x70], &[0x01, 0x2e]);
+        let esp3_packet = esp3_of_enocean_message(&message).unwrap();
+        assert_eq!(
+            esp3_packet.data,
+            DataType::Erp2Data {
+                header: 0x20,
+                extended_header: None,
+                extended_type: None,
+                rorg: Rorg::Rps,
+                sender_id: Address::from([0xfe, 0xf5, 0x8f, 0xf5]),
+                destination_id: None,
+                payload: vec![0x70],
+                optional: vec![],
+            }
+        );
+        assert_eq!(esp3_packet.opt_data(), Some(&OptDataType::Erp2OptData { subtel_num: 1, rssi: 0x2e }));
+        assert_eq!(esp3_packet.opt_data().and_then(OptDataType::rssi_dbm), Some(-46));
+        assert_eq!(esp3_packet.opt_data().and_then(OptDataType::security_level), None);
+        assert_eq!(Vec::from(&esp3_packet), message);
+        // EEP parsers run on ERP2 telegrams too
+        let parsed = crate::eep::parse_erp1_payload(&esp3_packet).unwrap();
+        assert_eq!(parsed.get("BTN").unwrap(), &String::from("Pressed"));
+
+        // 4BS telegram with destination ID, extended header (2 bytes of optional data)
+        let data = [0x52, 0x02, 0x05, 0x11, 0x72, 0xf7, 0xff, 0x9b, 0x12, 0x80, 0x00, 0xe5, 0xcc, 0x0a, 0xaa, 0xbb];
+        let message = build_message(0x0a, &data, &[]);
+        let esp3_packet = esp3_of_enocean_message(&message).unwrap();
+        match &esp3_packet.data {
+            DataType::Erp2Data { rorg, sender_id, destination_id, payload, optional, .. } => {
+                assert_eq!(rorg, &Rorg::Bs4);
+                assert_eq!(sender_id, &Address::from([0x05, 0x11, 0x72, 0xf7]));
+                assert_eq!(destination_id, &Some(Address::from([0xff, 0x9b, 0x12, 0x80])));
+                assert_eq!(payload, &vec![0x00, 0xe5, 0xcc, 0x0a]);
+                assert_eq!(optional, &vec![0xaa, 0xbb]);
+            }
+            data => panic!("Expected ERP2 data, got {:?}", data),
+        }
+        assert_eq!(Vec::from(&esp3_packet), message);
+
+        // 24 bits originator ID, extended telegram type (VLD)
+        let message = build_message(0x0a, &[0x0f, 0xd2, 0x0a, 0x3d, 0x6a, 0x04, 0x60, 0x80], &[]);
+        let esp3_packet = esp3_of_enocean_message(&message).unwrap();
+        match &esp3_packet.data {
+            DataType::Erp2Data { rorg, sender_id, payload, .. } => {
+                assert_eq!(rorg, &Rorg::Vld);
+                assert_eq!(sender_id, &Address::from([0x00, 0x0a, 0x3d, 0x6a]));
+                assert_eq!(payload, &vec![0x04, 0x60, 0x80]);
+            }
+            data => panic!("Expected ERP2 data, got {:?}", data),
+        }
+        assert_eq!(Vec::from(&esp3_packet), message);
+
+        // Extended telegram type which is not a known RORG
+        let message = build_message(0x0a, &[0x2f, 0x42, 0x01, 0x02, 0x03, 0x04, 0x99], &[]);
+        let esp3_packet = esp3_of_enocean_message(&message).unwrap();
+        match &esp3_packet.data {
+            DataType::Erp2Data { rorg, extended_type, payload, .. } => {
+                assert_eq!(rorg, &Rorg::Undefined);
+                assert_eq!(extended_type, &Some(0x42));
+                assert_eq!(payload, &vec![0x99]);
+            }
+            data => panic!("Expected ERP2 data, got {:?}", data),
+        }
+        assert_eq!(Vec::from(&esp3_packet), message);
+
+        // Truncated originator ID
+        let message = build_message(0x0a, &[0x20, 0xfe, 0xf5], &[]);
+        assert_eq!(esp3_of_enocean_message(&message).unwrap_err().kind, ParseEspErrorKind::IncompleteMessage);
+    }
+
     #[test]
     fn given_valid_response_packet_then_return_corresponding_esp() {
         let header: Vec<u8> = vec![0, 01, 0, 2];
@@ -684,6 +1511,249 @@ mod tests {
         assert_eq!(result_payload.is_none(), true);
     }
 
+    #[test]
+    fn given_valid_response_packet_with_payload_then_return_corresponding_esp() {
+        // Common command : read Base_ID of TCM300
+        let received_message = vec![85, 0, 5, 1, 2, 219, 0, 255, 155, 18, 128, 10, 17];
+        let esp3_packet = esp3_of_enocean_message(&received_message).unwrap();
+        assert_eq!(
+            esp3_packet.data,
+            DataType::ResponseData {
+                return_code: ReturnCode::Ok,
+                response_payload: Some(vec![255, 155, 18, 128]),
+            }
+        );
+    }
+
+    #[test]
+    fn given_response_with_zero_or_one_data_byte_then_require_the_return_code() {
+        // Data length 0 : CRCs only, no return code
+        let message = build_message(0x02, &[], &[]);
+        assert_eq!(message.len(), 7);
+        let error = esp3_of_enocean_message(&message).unwrap_err();
+        assert_eq!(error.kind, ParseEspErrorKind::IncompleteMessage);
+        assert_eq!(error.byte_index, Some(6));
+
+        // Data length 1 : return code only
+        let message = build_message(0x02, &[0x02], &[]);
+        assert_eq!(
+            esp3_of_enocean_message(&message).unwrap().data,
+            DataType::ResponseData { return_code: ReturnCode::NotSupported, response_payload: None }
+        );
+    }
+
+    #[test]
+    fn given_parsed_a50401_packet_then_read_its_rssi_from_optional_data() {
+        let received_message = [85, 0, 10, 7, 1, 235, 165, 0, 229, 204, 10, 5, 17, 114, 247, 0, 1, 255, 255, 255, 255, 54, 0, 213];
+        let esp3_packet = esp3_of_enocean_message(&received_message).unwrap();
+        match esp3_packet.opt_data() {
+            Some(OptDataType::Erp1OptData { subtel_num, destination_id, rssi, security_lvl }) => {
+                assert_eq!((*subtel_num, *destination_id, *rssi, *security_lvl), (1, [255; 4], 54, 0));
+            }
+            other => panic!("Expected ERP1 optional data, got {:?}", other),
+        }
+        assert_eq!(esp3_packet.opt_data().and_then(OptDataType::rssi_dbm), Some(-54));
+    }
+
+    #[test]
+    fn given_parsed_frame_then_keep_the_received_bytes() {
+        let original_bytes = [85, 0, 10, 7, 1, 235, 165, 0, 229, 204, 10, 5, 17, 114, 247, 0, 1, 255, 255, 255, 255, 54, 0, 213];
+        let parsed = ParsedFrame::parse(&original_bytes).unwrap();
+        assert_eq!(parsed.raw, original_bytes.to_vec());
+        assert_eq!(parsed.esp3, esp3_of_enocean_message(&original_bytes).unwrap());
+
+        // Trailing bytes are not part of the frame
+        let mut padded = original_bytes.to_vec();
+        padded.extend_from_slice(&[0, 0]);
+        assert_eq!(ParsedFrame::parse(&padded).unwrap().raw, original_bytes.to_vec());
+        assert!(ParsedFrame::parse(&original_bytes[..20]).is_err());
+    }
+
+    #[test]
+    fn given_new_response_then_round_trip_it_through_bytes() {
+        let empty = ESP3::new_response(ReturnCode::Ok, None);
+        let bytes = Vec::from(&empty);
+        assert_eq!(bytes, vec![85, 0, 1, 0, 2, 101, 0, 0]);
+        assert_eq!(esp3_of_enocean_message(&bytes).unwrap(), empty);
+
+        let base_id = ESP3::new_response(ReturnCode::Ok, Some(vec![255, 155, 18, 128]));
+        assert_eq!(base_id.kind(), PacketType::Response);
+        assert_eq!(
+            base_id.data,
+            DataType::ResponseData { return_code: ReturnCode::Ok, response_payload: Some(vec![255, 155, 18, 128]) }
+        );
+        assert_eq!(esp3_of_enocean_message(&Vec::from(&base_id)).unwrap(), base_id);
+    }
+
+    #[test]
+    fn given_received_and_constructed_telegrams_then_interpret_optional_data() {
+        // Received A5-04-01 telegram : 1 subtelegram, -54 dBm, not processed
+        let received = [85, 0, 10, 7, 1, 235, 165, 0, 229, 204, 10, 5, 17, 114, 247, 0, 1, 255, 255, 255, 255, 54, 0, 213];
+        let opt_data = esp3_of_enocean_message(&received).unwrap().opt_data.unwrap();
+        assert_eq!(opt_data.subtel_num(), Some(SubtelNum::Receive));
+        assert_eq!(opt_data.rssi_dbm(), Some(-54));
+        assert_eq!(opt_data.security_level(), Some(0));
+
+        // Telegram to send : subtel num 3, dBm 0xFF
+        let sent = crate::eep::create_smart_plug_command([0x05, 0x0a, 0x3d, 0x6a], crate::eep::D201CommandList::On).unwrap();
+        let opt_data = sent.opt_data().unwrap();
+        assert_eq!(opt_data.subtel_num(), Some(SubtelNum::Send));
+        assert_eq!(opt_data.rssi_dbm(), None);
+        assert_eq!(opt_data.security_level(), None);
+
+        // 3 subtelegrams received
+        let opt_data = OptDataType::Erp1OptData { subtel_num: 3, destination_id: [0xff; 4], rssi: 70, security_lvl: 0 };
+        assert_eq!(opt_data.rssi_dbm(), Some(-70));
+    }
+
+    #[test]
+    fn given_hex_dump_then_parse_esp3() {
+        let received_message = [
+            85, 0, 10, 7, 1, 235, 165, 0, 229, 204, 10, 5, 17, 114, 247, 0, 1, 255, 255, 255, 255, 54, 0, 213,
+        ];
+        let expected = esp3_of_enocean_message(&received_message).unwrap();
+        let hex = "55 00 0A 07 01 EB A5 00 E5 CC 0A 05 11 72 F7 00 01 FF FF FF FF 36 00 D5";
+        assert_eq!(ESP3::from_hex(hex).unwrap(), expected);
+        assert_eq!(ESP3::from_hex(&hex.replace(' ', ":").to_lowercase()).unwrap(), expected);
+        assert_eq!(ESP3::from_hex(&hex.replace(' ', "")).unwrap(), expected);
+        assert_eq!(expected.to_hex(), hex);
+
+        let error = ESP3::from_hex("55 00 0A 07 01 EG").unwrap_err();
+        assert_eq!(error.kind, ParseEspErrorKind::InvalidValue);
+        assert_eq!(error.byte_index, Some(5));
+        assert!(ESP3::from_hex("55 00 0A 07 01 +E").is_err());
+        // Odd number of digits
+        assert_eq!(ESP3::from_hex("55 00 0A 07 01 E").unwrap_err().kind, ParseEspErrorKind::InvalidValue);
+    }
+
+    #[test]
+    fn given_frame_then_round_trip_it_through_hex() {
+        let frame = ESP3Frame::assemble(0x01, &[246, 0, 254, 245, 143, 212, 32], &[2, 255, 255, 255, 255, 48, 0]);
+        let hex = frame.to_hex();
+        assert_eq!(hex, "55 00 07 07 01 7A F6 00 FE F5 8F D4 20 02 FF FF FF FF 30 00 27");
+
+        let esp3_packet = ESP3::from_hex(&hex).unwrap();
+        assert_eq!(Vec::from(&esp3_packet), Borrow::<[u8]>::borrow(&frame));
+        assert_eq!(esp3_packet.to_hex(), hex);
+    }
+
+    #[test]
+    fn given_received_telegram_then_get_its_link_quality() {
+        // Received F6-01-01 telegram : 2 subtelegrams, best one at -48 dBm
+        let received = [85, 0, 7, 7, 1, 122, 246, 0, 254, 245, 143, 212, 32, 2, 255, 255, 255, 255, 48, 0, 39];
+        let esp3_packet = esp3_of_enocean_message(&received).unwrap();
+        assert_eq!(esp3_packet.link_quality(), Some(LinkQuality { subtel_count: 2, best_rssi_dbm: -48 }));
+
+        // Telegrams to send, or without optional data, have none
+        let sent = crate::eep::create_smart_plug_command([0x05, 0x0a, 0x3d, 0x6a], crate::eep::D201CommandList::On).unwrap();
+        assert_eq!(sent.link_quality(), None);
+        let without_opt_data = esp3_of_enocean_message(&build_message(0x01, &[246, 0, 254, 245, 143, 212, 32], &[])).unwrap();
+        assert_eq!(without_opt_data.link_quality(), None);
+    }
+
+    #[test]
+    fn given_buffer_with_placeholder_crcs_then_seal_it() {
+        let command = crate::eep::create_smart_plug_command([0x05, 0x0a, 0x3d, 0x6a], crate::eep::D201CommandList::On).unwrap();
+        let expected = Vec::from(&command);
+
+        // With a data CRC placeholder
+        let mut buf = expected.clone();
+        buf[5] = 0;
+        *buf.last_mut().unwrap() = 0;
+        seal_esp3(&mut buf).unwrap();
+        assert_eq!(buf, expected);
+
+        // Without data CRC
+        buf.pop();
+        buf[5] = 0;
+        seal_esp3(&mut buf).unwrap();
+        assert_eq!(buf, expected);
+
+        // Length fields not matching the buffer
+        buf.push(0);
+        assert_eq!(seal_esp3(&mut buf).unwrap_err().kind, ParseEspErrorKind::IncompleteMessage);
+        assert_eq!(seal_esp3(&mut vec![0x00; 8]).unwrap_err().kind, ParseEspErrorKind::NoSyncByte);
+    }
+
+    #[test]
+    fn given_a50401_telegram_then_format_it_by_field() {
+        let telegram = [85, 0, 10, 7, 1, 235, 165, 0, 229, 204, 10, 5, 17, 114, 247, 0, 1, 255, 255, 255, 255, 54, 0, 213];
+        assert_eq!(
+            format_telegram_hex(&telegram),
+            "Sync: 55 | Length: 00 0A | OptLen: 07 | Type: 01 | CRC8H: EB | \
+             Data: A5 00 E5 CC 0A 05 11 72 F7 00 | Opt: 01 FF FF FF FF 36 00 | CRC8D: D5"
+        );
+
+        // Truncated, or followed by other bytes
+        assert_eq!(format_telegram_hex(&telegram[..3]), "Sync: 55 | Length: 00 0A");
+        assert_eq!(format_telegram_hex(&[85, 0, 1, 0, 2, 0x6d, 0, 0x00, 0x55]).split(" | ").last(), Some("Extra: 55"));
+        assert_eq!(format_telegram_hex(&[]), "");
+    }
+
+    #[test]
+    fn given_enums_then_display_human_friendly_names() {
+        assert_eq!(Rorg::Bs4.to_string(), "4BS");
+        assert_eq!(Rorg::Bs1.to_string(), "1BS");
+        assert_eq!(ReturnCode::LockSet.to_string(), "Lock set");
+        assert_eq!(PacketType::RadioErp1.to_string(), "Radio ERP1");
+        // Debug is unchanged
+        assert_eq!(format!("{:?}", Rorg::Bs4), "Bs4");
+    }
+
+    #[test]
+    fn given_event_packet_then_expose_its_packet_type() {
+        // CO_READY event
+        let message = build_message(0x04, &[0x04, 0x00], &[]);
+        let esp3_packet = esp3_of_enocean_message(&message).unwrap();
+        assert_eq!(esp3_packet.kind(), PacketType::Event);
+        assert_eq!(esp3_packet.packet_type(), u8::from(PacketType::Event));
+    }
+
+    #[test]
+    fn given_erp1_frame_then_round_trip_through_esp3() {
+        let message = [85, 0, 7, 7, 1, 122, 246, 0, 254, 245, 143, 245, 32, 1, 255, 255, 255, 255, 45, 0, 139];
+        let frame = ESP3Frame::read_from(&mut &message[..]).unwrap();
+
+        let esp3_packet = ESP3::try_from(&frame).unwrap();
+        assert_eq!(esp3_packet.packet_type(), 0x01);
+        let round_trip = ESP3Frame::from(&esp3_packet);
+        let bytes: &[u8] = round_trip.borrow();
+        assert_eq!(bytes, &message[..]);
+    }
+
+    #[test]
+    fn given_response_frame_then_round_trip_through_esp3() {
+        let message = [85, 0, 5, 1, 2, 219, 0, 255, 155, 18, 128, 10, 17];
+        let frame = ESP3Frame::read_from(&mut &message[..]).unwrap();
+
+        let esp3_packet = ESP3::try_from(&frame).unwrap();
+        assert_eq!(esp3_packet.packet_type(), 0x02);
+        let round_trip = ESP3Frame::from(&esp3_packet);
+        let bytes: &[u8] = round_trip.borrow();
+        assert_eq!(bytes, &message[..]);
+    }
+
+    #[test]
+    fn given_a50401_esp3_then_borrow_it_as_radio_erp1() {
+        let received_message = vec![
+            85, 0, 10, 7, 1, 235, 165, 0, 229, 204, 10, 5, 17, 114, 247, 0, 1, 255, 255, 255, 255,
+            54, 0, 213,
+        ];
+        let esp3_packet = esp3_of_enocean_message(&received_message).unwrap();
+        let erp1 = RadioErp1::try_from(&esp3_packet).unwrap();
+        assert_eq!(erp1.choice, 0xa5);
+        assert_eq!(erp1.user_data, &[0, 229, 204, 10]);
+        assert_eq!(erp1.sender_id, Address::from([5, 17, 114, 247]));
+        assert_eq!(erp1.status, Status(0));
+        assert_eq!(erp1.subtel_num, Some(SubtelNum::Receive));
+        assert_eq!(erp1.destination, Some(crate::packet::BROADCAST));
+        assert_eq!(erp1.rssi_dbm(), Some(-54));
+        assert_eq!(erp1.security, Some(Security::None));
+
+        let response = esp3_of_enocean_message(&[0x55, 0x00, 0x01, 0x00, 0x02, 0x65, 0x00, 0x00]).unwrap();
+        assert!(matches!(RadioErp1::try_from(&response), Err(ParseError::UnsupportedPacketType)));
+    }
+
     // TELEGRAMS examples :
     //
     // A50401 when button is pushed
@@ -48,11 +48,16 @@
 //!
 
 use std::borrow::Borrow;
-use std::io::Read;
+use std::io::{ErrorKind, Read};
+use std::time::{Duration, Instant};
+use log::{debug, warn};
 
 use crate::FrameReadError;
 use crate::crc8::{compute_crc8, CRC8};
 
+/// How many bytes `ESP3Frame::read_from` skips, at most, looking for a valid header
+pub const DEFAULT_SCAN_LIMIT: usize = 4096;
+
 /// An owned ESP3 frame that has been CRC-checked. Backed by a single `Vec<u8>`,  Includes synchronization byte and CRCs.
 #[derive(Clone, Debug)]
 pub struct ESP3Frame {
@@ -84,44 +89,196 @@ impl ESP3Frame {
     }
 
     /// Read a frame from a buffered reader. Will perform header synchronization. Allocates exactly the space needed.
+    ///
+    /// Gives up with `FrameReadError::NoSync` after skipping `DEFAULT_SCAN_LIMIT` bytes without finding a valid header.
     pub fn read_from(reader: &mut impl Read) -> Result<Self, FrameReadError> {
+        Self::read_until(reader, None, DEFAULT_SCAN_LIMIT)
+    }
 
-        let mut header = [0; 6];
-        loop {  // Synchronize with start of packet
+    /// Same as `read_from`, but gives up with `FrameReadError::NoSync` after skipping `scan_limit` bytes
+    /// (out of sync bytes, or headers with a bad CRC) without finding a valid header.
+    pub fn read_from_with_scan_limit(reader: &mut impl Read, scan_limit: usize) -> Result<Self, FrameReadError> {
+        Self::read_until(reader, None, scan_limit)
+    }
 
-            reader.read(&mut header[0..1])?;
-            if header[0] != 0x55 {  // Look for synchronization byte
-                eprintln!("Reader out of sync. Skipping..");
-                continue;
-            }
+    /// Same as `read_from`, but gives up with `FrameReadError::Timeout` if no complete frame arrived within `timeout`.
+    ///
+    /// Read timeouts of the underlying reader (eg. a serial port configured with a timeout) are retried until the deadline.
+    /// The reader must have such a timeout, otherwise a read may block past the deadline.
+    pub fn read_from_timeout(reader: &mut impl Read, timeout: Duration) -> Result<Self, FrameReadError> {
+        Self::read_until(reader, Some(Instant::now() + timeout), DEFAULT_SCAN_LIMIT)
+    }
+
+    /// Read the first frame of a slice, which may be followed by other frames or padding.
+    ///
+    /// Returns the frame along with the number of bytes consumed (including any bytes skipped to synchronize),
+    /// so that concatenated frames can be parsed by advancing through the slice.
+    /// ```
+    /// # use enocean::frame::*;
+    /// let buffer = [85, 0, 1, 0, 2, 101, 0, 0, 0, 0];
+    /// let (frame, consumed) = ESP3Frame::read_from_allow_trailing(&buffer).unwrap();
+    /// assert_eq!(frame.packet_type(), 0x02);
+    /// assert_eq!(consumed, 8);
+    /// ```
+    pub fn read_from_allow_trailing(buffer: &[u8]) -> Result<(Self, usize), FrameReadError> {
+        let mut reader = buffer;
+        let frame = Self::read_from(&mut reader)?;
+        Ok((frame, buffer.len() - reader.len()))
+    }
 
-            reader.read(&mut header[1..6])?;
-            if compute_crc8(&header[1..6]) != 0 {  // Check header CRC. If it fails, keep looking for another sync byte.
-                eprintln!("Header CRC Failed. skipping..");
-                continue;
+    /// Iterate over the frames read from a buffered reader.
+    ///
+    /// The iterator ends at the end of the stream, or after yielding an IO error (except read timeouts).
+    /// Frames with a bad data CRC are yielded as errors, and reading goes on with the next frame.
+    /// ```
+    /// # use enocean::frame::*;
+    /// let stream = [85, 0, 1, 0, 2, 101, 0, 0, 85, 0, 1, 0, 2, 101, 0, 0];
+    /// for frame in ESP3Frame::iter_from(&mut &stream[..]) {
+    ///     assert_eq!(frame.unwrap().packet_type(), 0x02);
+    /// }
+    /// ```
+    pub fn iter_from<R: Read>(reader: &mut R) -> impl Iterator<Item = Result<Self, FrameReadError>> + '_ {
+        let mut done = false;
+        std::iter::from_fn(move || {
+            if done { return None }
+            match Self::read_from(reader) {
+                Err(FrameReadError::EOF) => {
+                    done = true;
+                    None
+                }
+                Err(FrameReadError::IOError(e)) if e.kind() != ErrorKind::TimedOut => {
+                    done = true;
+                    Some(Err(e.into()))
+                }
+                result => Some(result),
             }
+        })
+    }
 
-            break;
+    fn read_until(reader: &mut impl Read, deadline: Option<Instant>, scan_limit: usize) -> Result<Self, FrameReadError> {
+
+        // Like read_exact, but checks the deadline between reads
+        let mut read_exact = |buf: &mut [u8]| -> Result<(), FrameReadError> {
+            let mut filled = 0;
+            while filled < buf.len() {
+                if deadline.is_some_and(|d| Instant::now() >= d) { return Err(FrameReadError::Timeout) }
+                match reader.read(&mut buf[filled..]) {
+                    Ok(0) => return Err(FrameReadError::EOF),
+                    Ok(n) => filled += n,
+                    Err(e) if e.kind() == ErrorKind::Interrupted => (),
+                    Err(e) if e.kind() == ErrorKind::TimedOut && deadline.is_some() => (),
+                    Err(e) => return Err(e.into()),
+                }
+            }
+            Ok(())
         };
 
-        // The frame is now synchronized and the header CRC is valid
-        // decode the header
-        let data_length = ((header[1] as usize) << 8) + (header[2] as usize);
-        let optional_data_length = header[3] as usize;
-        let packet_type = header[4];
+        let mut header = [0; 6];
+        // Header bytes already read, from a sync byte
+        let mut filled = 0;
+        let mut skipped = 0;
+        // Synchronize with start of packet
+        let (packet_type, data_length, optional_data_length) = loop {
+            if filled == 0 {
+                if skipped >= scan_limit { return Err(FrameReadError::NoSync) }
+
+                read_exact(&mut header[0..1])?;
+                if header[0] != 0x55 {  // Look for synchronization byte
+                    debug!("Reader out of sync (byte {:#04x}). Skipping..", header[0]);
+                    skipped += 1;
+                    continue;
+                }
+                filled = 1;
+            }
+
+            read_exact(&mut header[filled..6])?;
+            match parse_header(&header) {
+                Some(fields) => break fields,
+                None => {  // If the header CRC fails, look for another sync byte, starting within the header.
+                    warn!("Header CRC Failed ({:02x?}). skipping..", &header[1..6]);
+                    filled = resync_header(&mut header);
+                    skipped = skipped.saturating_add(6 - filled).min(scan_limit);
+                }
+            }
+        };
 
         // Allocate an appropriate buffer
         let total_length = 6 + data_length + optional_data_length + 1;
         let mut frame = vec![0; total_length];
 
         frame[0..6].copy_from_slice(&header);
-        reader.read_exact(&mut frame[6..])?;
+        read_exact(&mut frame[6..])?;
+
+        Self::check_data_crc(frame, packet_type, data_length, optional_data_length)
+
+    }
 
-        // Check the Data CRC
+    /// Check the data CRC of a frame whose header was decoded by `parse_header`
+    fn check_data_crc(frame: Vec<u8>, packet_type: u8, data_length: usize, optional_data_length: usize) -> Result<Self, FrameReadError> {
         let data_crc = compute_crc8(&frame[6..]);
         if data_crc != 0 { return Err(FrameReadError::DataCRC{ frame, data_crc }) }
 
         Ok(ESP3Frame { frame, packet_type, data_length, optional_data_length })
+    }
+
+    /// Read a frame from an async buffered reader. Same as `read_from`, for async applications (requires the `async` feature).
+    #[cfg(feature = "async")]
+    pub async fn read_from_async(reader: &mut (impl tokio::io::AsyncBufRead + Unpin)) -> Result<Self, FrameReadError> {
+        Self::read_from_async_with_scan_limit(reader, DEFAULT_SCAN_LIMIT).await
+    }
+
+    /// Same as `read_from_async`, but gives up with `FrameReadError::NoSync` after skipping `scan_limit` bytes,
+    /// see `read_from_with_scan_limit`.
+    #[cfg(feature = "async")]
+    pub async fn read_from_async_with_scan_limit(
+        reader: &mut (impl tokio::io::AsyncBufRead + Unpin),
+        scan_limit: usize,
+    ) -> Result<Self, FrameReadError> {
+        use tokio::io::AsyncReadExt;
+
+        // End of stream is reported as an UnexpectedEof by read_exact
+        let eof = |e: std::io::Error| match e.kind() {
+            ErrorKind::UnexpectedEof => FrameReadError::EOF,
+            _ => FrameReadError::IOError(e),
+        };
+
+        let mut header = [0; 6];
+        // Header bytes already read, from a sync byte
+        let mut filled = 0;
+        let mut skipped = 0;
+        // Synchronize with start of packet
+        let (packet_type, data_length, optional_data_length) = loop {
+            if filled == 0 {
+                if skipped >= scan_limit { return Err(FrameReadError::NoSync) }
+
+                reader.read_exact(&mut header[0..1]).await.map_err(eof)?;
+                if header[0] != 0x55 {  // Look for synchronization byte
+                    debug!("Reader out of sync (byte {:#04x}). Skipping..", header[0]);
+                    skipped += 1;
+                    continue;
+                }
+                filled = 1;
+            }
+
+            reader.read_exact(&mut header[filled..6]).await.map_err(eof)?;
+            match parse_header(&header) {
+                Some(fields) => break fields,
+                None => {  // If the header CRC fails, look for another sync byte, starting within the header.
+                    warn!("Header CRC Failed ({:02x?}). skipping..", &header[1..6]);
+                    filled = resync_header(&mut header);
+                    skipped = skipped.saturating_add(6 - filled).min(scan_limit);
+                }
+            }
+        };
+
+        // Allocate an appropriate buffer
+        let total_length = 6 + data_length + optional_data_length + 1;
+        let mut frame = vec![0; total_length];
+
+        frame[0..6].copy_from_slice(&header);
+        reader.read_exact(&mut frame[6..]).await.map_err(eof)?;
+
+        Self::check_data_crc(frame, packet_type, data_length, optional_data_length)
 
     }
 
@@ -130,6 +287,21 @@ impl ESP3Frame {
         self.packet_type
     }
 
+    /// The complete frame, from the sync byte to the data CRC, eg. to forward it as is
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.frame
+    }
+
+    /// Length of the complete frame, in bytes
+    pub fn len(&self) -> usize {
+        self.frame.len()
+    }
+
+    /// Always false : a frame holds at least its header and CRCs
+    pub fn is_empty(&self) -> bool {
+        self.frame.is_empty()
+    }
+
     /// The frame mandatory, fixed-format data
     pub fn data(&self) -> &[u8] {
         &self.frame[6..][..self.data_length]
@@ -152,6 +324,50 @@ impl ESP3Frame {
     pub fn write_to(&self, writer: &mut impl std::io::Write) -> Result<(), std::io::Error> {
         writer.write_all(&self.frame)
     }
+
+    /// The complete frame as uppercase hex bytes separated by spaces (eg. `"55 00 07 07 01 7A ..."`), eg. for logs
+    pub fn to_hex(&self) -> String {
+        hex_string(&self.frame)
+    }
+}
+
+/// Check the sync byte and CRC of a header, and decode it into (packet type, data length, optional data length)
+fn parse_header(header: &[u8; 6]) -> Option<(u8, usize, usize)> {
+    if header[0] != 0x55 || compute_crc8(&header[1..6]) != 0 { return None }
+    let data_length = ((header[1] as usize) << 8) + (header[2] as usize);
+    Some((header[4], data_length, header[3] as usize))
+}
+
+/// After a header CRC failure, move the header bytes from its next sync byte to its start.
+/// Returns how many bytes were kept, 0 if there is no other sync byte in the header.
+fn resync_header(header: &mut [u8; 6]) -> usize {
+    match header[1..].iter().position(|&byte| byte == 0x55) {
+        Some(index) => {
+            header.copy_within(index + 1.., 0);
+            5 - index
+        }
+        None => 0,
+    }
+}
+
+/// Uppercase hex bytes separated by spaces
+pub(crate) fn hex_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02X}", byte)).collect::<Vec<_>>().join(" ")
+}
+
+/// Split a recorded byte stream into frames, eg. for the offline analysis of captured telegrams.
+///
+/// Garbage before and between frames is skipped. Frames with a bad data CRC are reported as errors, and splitting goes on after them.
+/// A truncated frame at the end of `buf` is ignored.
+pub fn frames_from_bytes(buf: &[u8]) -> Vec<Result<ESP3Frame, FrameReadError>> {
+    let mut reader = buf;
+    let mut frames = vec![];
+    loop {
+        match ESP3Frame::read_from_with_scan_limit(&mut reader, usize::MAX) {
+            Err(FrameReadError::EOF) => return frames,
+            result => frames.push(result),
+        }
+    }
 }
 
 impl Borrow<[u8]> for ESP3Frame {
@@ -166,8 +382,20 @@ impl<'a> From<ESP3FrameRef<'a>> for ESP3Frame {
 
 impl<'a> ESP3FrameRef<'a> {
 
-    /// Generate and write a frame
+    /// Generate and write a frame, as a single contiguous write
     pub fn write_to(&self, writer: &mut impl std::io::Write) -> Result<(), std::io::Error> {
+        let mut frame = Vec::with_capacity(self.frame_length());
+        self.extend_into(&mut frame);
+        writer.write_all(&frame)
+    }
+
+    /// Length of the complete frame, including synchronization byte and CRCs
+    fn frame_length(&self) -> usize {
+        6 + self.data.len() + self.optional_data.len() + 1
+    }
+
+    // Appends the complete frame to `frame`
+    fn extend_into(&self, frame: &mut Vec<u8>) {
 
         // Build the header
         let data_len = self.data.len() as u16;
@@ -179,22 +407,22 @@ impl<'a> ESP3FrameRef<'a> {
 
         // CRC the header
         header[5] = CRC8::from(&header[1..5]).into();
-        writer.write_all(&header[..])?;
+        frame.extend_from_slice(&header[..]);
 
         // CRC the payload
         let data_crc = CRC8::from(self.data).extend(self.optional_data).into();
 
         // Build the payload
-        writer.write_all(self.data)?;
-        writer.write_all(self.optional_data)?;
-        writer.write_all(&[data_crc])
+        frame.extend_from_slice(self.data);
+        frame.extend_from_slice(self.optional_data);
+        frame.push(data_crc);
 
     }
 
     // Copies the pieces of a constructed ESP3FrameRef into a single-buffer owned ESP3Frame
     pub fn to_owned(&self) -> ESP3Frame {
-        let mut frame = Vec::with_capacity(6 + self.data.len() + self.optional_data.len() + 1);
-        self.write_to(&mut frame).unwrap();   // Writing to a preallocated Vec should never fail
+        let mut frame = Vec::with_capacity(self.frame_length());
+        self.extend_into(&mut frame);
 
         ESP3Frame { packet_type: self.packet_type,
                     data_length: self.data.len(),
@@ -203,4 +431,221 @@ impl<'a> ESP3FrameRef<'a> {
 
     }
 
-}
\ No newline at end of file
+}
+
+/// Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A reader which never delivers any byte, like a quiet serial line with a read timeout.
+    struct QuietLine;
+
+    impl Read for QuietLine {
+        fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::new(ErrorKind::TimedOut, "Operation timed out"))
+        }
+    }
+
+    #[test]
+    fn given_quiet_line_then_read_from_timeout_returns_timeout() {
+        let result = ESP3Frame::read_from_timeout(&mut QuietLine, Duration::from_millis(10));
+        assert!(matches!(result, Err(FrameReadError::Timeout)));
+    }
+
+    #[test]
+    fn given_quiet_line_then_read_from_returns_io_error() {
+        let result = ESP3Frame::read_from(&mut QuietLine);
+        assert!(matches!(result, Err(FrameReadError::IOError(e)) if e.kind() == ErrorKind::TimedOut));
+    }
+
+    #[test]
+    fn given_valid_frame_then_read_from_timeout_returns_it() {
+        let frame_bin = [85, 0, 1, 0, 2, 101, 0, 0];
+        let frame = ESP3Frame::read_from_timeout(&mut &frame_bin[..], Duration::from_secs(1)).unwrap();
+        assert_eq!(frame.packet_type(), 0x02);
+        assert_eq!(frame.data(), &[0]);
+        assert_eq!(frame.as_bytes(), &frame_bin);
+        assert_eq!(frame.len(), frame_bin.len());
+    }
+
+    #[test]
+    fn given_concatenated_frames_and_garbage_then_iterate_over_frames() {
+        let mut stream = vec![];
+        ESP3Frame::assemble(0x01, &[246, 112, 254, 245, 143, 245, 48], &[1, 255, 255, 255, 255, 46, 0]).write_to(&mut stream).unwrap();
+        ESP3Frame::assemble(0x02, &[0], &[]).write_to(&mut stream).unwrap();
+        stream.extend_from_slice(&[0x12, 0x55, 0x00, 0x34]);
+
+        let frames: Vec<_> = ESP3Frame::iter_from(&mut &stream[..]).collect();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].as_ref().unwrap().packet_type(), 0x01);
+        assert_eq!(frames[1].as_ref().unwrap().packet_type(), 0x02);
+    }
+
+    #[test]
+    fn given_slice_of_frames_and_partial_byte_then_read_them_advancing_by_consumed_length() {
+        let mut buffer = vec![];
+        ESP3Frame::assemble(0x01, &[246, 112, 254, 245, 143, 245, 48], &[1, 255, 255, 255, 255, 46, 0]).write_to(&mut buffer).unwrap();
+        ESP3Frame::assemble(0x02, &[0], &[]).write_to(&mut buffer).unwrap();
+        buffer.push(0x55);
+
+        let (first, consumed) = ESP3Frame::read_from_allow_trailing(&buffer).unwrap();
+        assert_eq!(first.packet_type(), 0x01);
+        assert_eq!(consumed, 21);
+        let (second, consumed) = ESP3Frame::read_from_allow_trailing(&buffer[21..]).unwrap();
+        assert_eq!(second.packet_type(), 0x02);
+        assert_eq!(consumed, 8);
+        assert!(matches!(ESP3Frame::read_from_allow_trailing(&buffer[29..]), Err(FrameReadError::EOF)));
+    }
+
+    #[test]
+    fn given_frame_with_bad_data_crc_then_yield_error_and_continue() {
+        let mut stream = vec![85, 0, 1, 0, 2, 101, 0, 42];
+        ESP3Frame::assemble(0x02, &[0], &[]).write_to(&mut stream).unwrap();
+
+        let mut reader = &stream[..];
+        let mut frames = ESP3Frame::iter_from(&mut reader);
+        assert!(matches!(frames.next(), Some(Err(FrameReadError::DataCRC { .. }))));
+        assert_eq!(frames.next().unwrap().unwrap().packet_type(), 0x02);
+        assert!(frames.next().is_none());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn given_noise_before_valid_frame_then_read_from_async_resyncs() {
+        // Noise, including a sync byte with a bad header CRC, then a valid frame
+        let mut stream = vec![0x12, 0x34, 0x55, 0x00, 0x01, 0x00, 0x02, 0x00, 0xff];
+        ESP3Frame::assemble(0x01, &[246, 112, 254, 245, 143, 245, 48], &[1, 255, 255, 255, 255, 46, 0]).write_to(&mut stream).unwrap();
+
+        let mut reader = std::io::Cursor::new(stream);
+        let frame = ESP3Frame::read_from_async(&mut reader).await.unwrap();
+        assert_eq!(frame.packet_type(), 0x01);
+        assert_eq!(frame.data(), &[246, 112, 254, 245, 143, 245, 48]);
+        assert!(matches!(ESP3Frame::read_from_async(&mut reader).await, Err(FrameReadError::EOF)));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn given_stray_sync_byte_before_frame_then_read_from_async_keeps_the_frame() {
+        let mut stream = vec![0x55];
+        ESP3Frame::assemble(0x02, &[0], &[]).write_to(&mut stream).unwrap();
+
+        let mut reader = std::io::Cursor::new(stream);
+        assert_eq!(ESP3Frame::read_from_async(&mut reader).await.unwrap().packet_type(), 0x02);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn given_long_run_of_garbage_then_read_from_async_gives_up_after_scan_limit() {
+        let mut stream = vec![0x12; 200];
+        ESP3Frame::assemble(0x02, &[0], &[]).write_to(&mut stream).unwrap();
+
+        let mut reader = std::io::Cursor::new(stream.clone());
+        assert!(matches!(ESP3Frame::read_from_async_with_scan_limit(&mut reader, 100).await, Err(FrameReadError::NoSync)));
+        let mut reader = std::io::Cursor::new(stream);
+        assert_eq!(ESP3Frame::read_from_async_with_scan_limit(&mut reader, 201).await.unwrap().packet_type(), 0x02);
+    }
+
+    /// A writer recording each write call separately
+    #[derive(Default)]
+    struct WriteCalls(Vec<Vec<u8>>);
+
+    impl std::io::Write for WriteCalls {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.push(buf.to_vec());
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn given_frame_ref_then_write_it_in_a_single_call() {
+        let data = &[165, 16, 8, 70, 128, 5, 17, 114, 247, 0];
+        let optional_data = &[1, 255, 255, 255, 255, 55, 0];
+        let mut writer = WriteCalls::default();
+        ESP3FrameRef { packet_type: 1, data, optional_data }.write_to(&mut writer).unwrap();
+
+        assert_eq!(
+            writer.0,
+            vec![vec![85, 0, 10, 7, 1, 235, 165, 16, 8, 70, 128, 5, 17, 114, 247, 0, 1, 255, 255, 255, 255, 55, 0, 55]]
+        );
+    }
+
+    #[test]
+    fn given_headers_then_decode_only_synchronized_ones_with_valid_crc() {
+        let header = [0x55, 0x00, 0x07, 0x07, 0x01, 122];
+        assert_eq!(parse_header(&header), Some((0x01, 7, 7)));
+        assert_eq!(parse_header(&[0x55, 0x00, 0x07, 0x07, 0x01, 123]), None);
+        assert_eq!(parse_header(&[0x54, 0x00, 0x07, 0x07, 0x01, 122]), None);
+    }
+
+    #[test]
+    fn given_long_run_of_garbage_then_give_up_after_scan_limit() {
+        let mut stream = vec![0x12; 5000];
+        ESP3Frame::assemble(0x02, &[0], &[]).write_to(&mut stream).unwrap();
+
+        let mut reader = &stream[..];
+        assert!(matches!(ESP3Frame::read_from(&mut reader), Err(FrameReadError::NoSync)));
+        // Scanning resumes where it stopped
+        assert_eq!(ESP3Frame::read_from(&mut reader).unwrap().packet_type(), 0x02);
+
+        assert!(matches!(ESP3Frame::read_from_with_scan_limit(&mut &stream[..], 100), Err(FrameReadError::NoSync)));
+        assert!(matches!(ESP3Frame::read_from_with_scan_limit(&mut &stream[..], 5000), Err(FrameReadError::NoSync)));
+        assert_eq!(ESP3Frame::read_from_with_scan_limit(&mut &stream[..], 5001).unwrap().packet_type(), 0x02);
+    }
+
+    #[test]
+    fn given_bad_header_then_count_it_in_the_scan_limit() {
+        // A sync byte with a bad header CRC skips 6 bytes, which is more than the limit
+        let mut stream = vec![0x55, 0x00, 0x01, 0x00, 0x02, 0x00];
+        ESP3Frame::assemble(0x02, &[0], &[]).write_to(&mut stream).unwrap();
+
+        let mut reader = &stream[..];
+        assert!(matches!(ESP3Frame::read_from_with_scan_limit(&mut reader, 3), Err(FrameReadError::NoSync)));
+        assert_eq!(ESP3Frame::read_from_with_scan_limit(&mut reader, 3).unwrap().packet_type(), 0x02);
+        assert_eq!(ESP3Frame::read_from_with_scan_limit(&mut &stream[..], 7).unwrap().packet_type(), 0x02);
+    }
+
+    #[test]
+    fn given_recorded_bytes_with_garbage_then_split_them_into_frames() {
+        let mut buf = vec![0x12, 0x34];
+        ESP3Frame::assemble(0x01, &[246, 112, 254, 245, 143, 245, 48], &[1, 255, 255, 255, 255, 46, 0]).write_to(&mut buf).unwrap();
+        buf.extend_from_slice(&[0x9a, 0x00, 0xc3]);
+        buf.extend_from_slice(&[85, 0, 1, 0, 2, 101, 0, 42]); // Bad data CRC
+        ESP3Frame::assemble(0x02, &[0], &[]).write_to(&mut buf).unwrap();
+        buf.extend_from_slice(&[0x7e, 0x55, 0x00]);
+
+        let frames = frames_from_bytes(&buf);
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[0].as_ref().unwrap().packet_type(), 0x01);
+        assert!(matches!(frames[1], Err(FrameReadError::DataCRC { .. })));
+        assert_eq!(frames[2].as_ref().unwrap().packet_type(), 0x02);
+    }
+
+    #[test]
+    fn given_stray_sync_bytes_before_frames_then_keep_the_frames() {
+        let erp1 = ESP3Frame::assemble(0x01, &[246, 112, 254, 245, 143, 245, 48], &[1, 255, 255, 255, 255, 46, 0]);
+        let mut buf = vec![0x55];
+        erp1.write_to(&mut buf).unwrap();
+        ESP3Frame::assemble(0x02, &[0], &[]).write_to(&mut buf).unwrap();
+
+        let frames = frames_from_bytes(&buf);
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].as_ref().unwrap().data(), erp1.data());
+        assert_eq!(frames[1].as_ref().unwrap().packet_type(), 0x02);
+
+        // Sync byte within a bad header
+        let mut buf = vec![0x55, 0x12, 0x55, 0x34];
+        erp1.write_to(&mut buf).unwrap();
+        assert_eq!(ESP3Frame::read_from(&mut &buf[..]).unwrap().data(), erp1.data());
+    }
+
+    #[test]
+    fn given_truncated_stream_then_return_eof() {
+        let frame_bin = [0, 85, 0, 1, 0, 2, 101];
+        assert!(matches!(ESP3Frame::read_from(&mut &frame_bin[..]), Err(FrameReadError::EOF)));
+        assert!(matches!(ESP3Frame::read_from(&mut &[][..]), Err(FrameReadError::EOF)));
+    }
+}
@@ -1,6 +1,5 @@
 extern crate serialport;
 
-use std::error::Error as StdError;
 use std::fmt;
 
 use thiserror::Error;
@@ -8,17 +7,20 @@ use thiserror::Error;
 // Differents file which should be linked
 pub mod communicator;
 pub mod crc8;
+pub mod dedup;
 pub mod eep;
 pub mod enocean;
 pub mod frame;
+pub mod mock;
 pub mod packet;
 pub mod port;
+pub mod util;
 
 /// Custom Result type = std::result::Result<T, ParseEspError>
 type ParseEspResult<T> = std::result::Result<T, ParseEspError>;
 
 /// Custom error type (eg. allow to see corresponding packet / byte index )
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Error)]
 pub struct ParseEspError {
     /// ErrorKind
     pub kind: ParseEspErrorKind,
@@ -33,9 +35,14 @@ pub struct ParseEspError {
 #[derive(Debug, Clone, PartialEq)]
 pub enum ParseEspErrorKind {
     NoSyncByte,
-    CrcMismatch,
+    /// CRC `computed` from the received bytes, and `expected` CRC byte as received
+    CrcMismatch { computed: u8, expected: u8 },
     IncompleteMessage,
     Unimplemented,
+    /// A value given to build a packet is out of its allowed range
+    InvalidValue,
+    /// The sender of a telegram is not among the allowed ones
+    UnknownSender,
 }
 
 /// The type of errors that may occur while reading/decoding a frame.
@@ -48,8 +55,12 @@ pub enum FrameReadError {
     #[error("IO Error")]            IOError(#[from] std::io::Error),
     /// The reader reached end of stream before delivering a complete packet
     #[error("End of Stream")]       EOF,
+    /// No complete frame was received before the deadline
+    #[error("Timeout")]             Timeout,
     /// The data CRC of the packet was incorrect
     #[error("Bad CRC for data")]    DataCRC{ frame: Vec<u8>, data_crc: u8 },
+    /// No valid header was found within the scanned bytes, see `ESP3Frame::read_from_with_scan_limit`
+    #[error("No sync byte found")]  NoSync,
 }
 
 #[derive(Debug,Error)]
@@ -57,10 +68,27 @@ pub enum PacketError {
     #[error("Could not read frame")]  FrameError(#[from] FrameReadError),
     #[error("Could not parse frame")] ParseError(#[from] packet::ParseError),
     #[error("IO Error")]              IOError(#[from] std::io::Error),
+    #[error("No response received")]  NoResponse,
+    #[error("Write denied")]          WriteDenied(packet::ResponseCode),
+    #[error("Invalid parameter")]     InvalidParameter(&'static str),
+}
+
+/// Why the [`communicator`] read/send loop stopped
+#[derive(Debug, Error)]
+pub enum CommunicatorError {
+    /// The serial port could not be opened, read or written
+    #[error("IO Error")]                  IO(#[from] std::io::Error),
+    /// The serial port was closed or unplugged
+    #[error("Serial port disconnected")]  PortDisconnected,
+    /// The receiver of incoming packets was dropped
+    #[error("Event channel closed")]      ChannelClosed,
 }
 
 impl fmt::Display for ParseEspError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let ParseEspErrorKind::CrcMismatch { computed, expected } = self.kind {
+            write!(f, "CRC computed {:#04x}, expected {:#04x} : ", computed, expected)?;
+        }
         match self.byte_index {
             // Error chould occur on a specific byte
             Some(bi) => write!(
@@ -77,11 +105,26 @@ impl fmt::Display for ParseEspError {
         }
     }
 }
-impl StdError for ParseEspError {
-    fn description(&self) -> &str {
-        &self.message
-    }
-}
 
 /// Working with the type EnoceanMessage is more explicit than u8 vector.
 type EnoceanMessage = Vec<u8>;
+
+/// Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error as StdError;
+
+    fn parse_boxed(message: &[u8]) -> Result<enocean::ESP3, Box<dyn StdError>> {
+        Ok(enocean::esp3_of_enocean_message(message)?)
+    }
+
+    #[test]
+    fn given_parse_esp_error_then_convert_it_into_boxed_std_error() {
+        let error = parse_boxed(&[0x00, 0x00, 0x01, 0x00, 0x05, 0x70, 0x00, 0x00]).unwrap_err();
+        assert!(error.source().is_none());
+        let error = error.downcast::<ParseEspError>().unwrap();
+        assert_eq!(error.kind, ParseEspErrorKind::NoSyncByte);
+        assert!(error.to_string().starts_with("NoSyncByte error"));
+    }
+}
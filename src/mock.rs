@@ -0,0 +1,95 @@
+//! In-memory stand-in for a serial port, for testing code which talks to an ESP3 device
+
+use std::collections::VecDeque;
+use std::io::{self, Read, Write};
+
+/// A fake serial port : reads play back scripted byte chunks, writes are recorded.
+///
+/// Each `read` returns (at most) one chunk, like a real serial port returning what was received so far.
+/// Once every chunk has been read, `read` fails with `io::ErrorKind::BrokenPipe`.
+#[derive(Debug, Default)]
+pub struct MockPort {
+    chunks: VecDeque<Vec<u8>>,
+    written: Vec<u8>,
+    flushes: usize,
+}
+
+impl MockPort {
+    /// A mock port which will return `chunks`, one per read
+    pub fn new<I, C>(chunks: I) -> Self
+    where
+        I: IntoIterator<Item = C>,
+        C: Into<Vec<u8>>,
+    {
+        MockPort {
+            chunks: chunks.into_iter().map(Into::into).collect(),
+            written: Vec::new(),
+            flushes: 0,
+        }
+    }
+
+    /// Queue another chunk, returned after the previous ones
+    pub fn push_chunk(&mut self, chunk: impl Into<Vec<u8>>) {
+        self.chunks.push_back(chunk.into());
+    }
+
+    /// Every byte written to the port so far
+    pub fn written(&self) -> &[u8] {
+        &self.written
+    }
+
+    /// How many times the port was flushed
+    pub fn flush_count(&self) -> usize {
+        self.flushes
+    }
+}
+
+impl Read for MockPort {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let chunk = match self.chunks.front_mut() {
+            Some(chunk) => chunk,
+            None => return Err(io::Error::new(io::ErrorKind::BrokenPipe, "No more scripted chunks")),
+        };
+        let n = chunk.len().min(buf.len());
+        buf[..n].copy_from_slice(&chunk[..n]);
+        chunk.drain(..n);
+        if chunk.is_empty() {
+            self.chunks.pop_front();
+        }
+        Ok(n)
+    }
+}
+
+impl Write for MockPort {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.written.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.flushes += 1;
+        Ok(())
+    }
+}
+
+/// Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn given_scripted_chunks_then_read_them_one_by_one() {
+        let mut port = MockPort::new([vec![1, 2, 3], vec![4]]);
+        let mut buf = [0; 2];
+        assert_eq!(port.read(&mut buf).unwrap(), 2);
+        assert_eq!(buf, [1, 2]);
+        assert_eq!(port.read(&mut buf).unwrap(), 1);
+        assert_eq!(buf[0], 3);
+        assert_eq!(port.read(&mut buf).unwrap(), 1);
+        assert_eq!(buf[0], 4);
+        assert_eq!(port.read(&mut buf).unwrap_err().kind(), io::ErrorKind::BrokenPipe);
+
+        port.write_all(&[0x55, 0x00]).unwrap();
+        assert_eq!(port.written(), &[0x55, 0x00]);
+    }
+}
@@ -1,6 +1,7 @@
 //! ESP3 packet encoding and decoding
 
-use std::str::Utf8Error;
+use std::fmt;
+use std::str::{FromStr, Utf8Error};
 
 use num_enum::TryFromPrimitive;
 use thiserror::Error;
@@ -9,12 +10,81 @@ use crate::frame::{ESP3Frame, ESP3FrameRef};
 
 pub type ResponseCode = crate::enocean::ReturnCode;
 
-#[derive(Debug,Clone,Copy)]
+/// EnOcean ID of a device, written `AA:BB:CC:DD`
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash)]
 pub struct Address([u8; 4]);
 
 pub const BROADCAST: Address = Address([0xff,0xff,0xff,0xff]);
 
-pub struct EEPProfileCode([u8; 3]);
+impl Address {
+    pub fn as_bytes(&self) -> &[u8; 4] { &self.0 }
+}
+
+impl From<[u8; 4]> for Address {
+    fn from(id: [u8; 4]) -> Self { Address(id) }
+}
+
+impl From<Address> for [u8; 4] {
+    fn from(address: Address) -> Self { address.0 }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let [a, b, c, d] = self.0;
+        write!(f, "{:02X}:{:02X}:{:02X}:{:02X}", a, b, c, d)
+    }
+}
+
+#[derive(Debug,Error)]
+#[error("Invalid address, expected 4 hex bytes as AA:BB:CC:DD")]
+pub struct ParseAddressError;
+
+impl FromStr for Address {
+    type Err = ParseAddressError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut id = [0u8; 4];
+        let mut bytes = s.split(':');
+        for byte in id.iter_mut() {
+            let hex = bytes.next()
+                .filter(|hex| hex.len() == 2 && hex.bytes().all(|c| c.is_ascii_hexdigit()))
+                .ok_or(ParseAddressError)?;
+            *byte = u8::from_str_radix(hex, 16).map_err(|_| ParseAddressError)?;
+        }
+        match bytes.next() {
+            None => Ok(Address(id)),
+            Some(_) => Err(ParseAddressError),
+        }
+    }
+}
+
+/// Status byte of an ERP1 telegram
+///
+/// For RPS telegrams (eg. F6-02-xx switches), bits 5 and 4 are the T21 and NU flags, bits 3..0 the repeater count.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct Status(pub u8);
+
+impl Status {
+    /// How many times the telegram was repeated (0 for an original telegram)
+    pub fn repeater_count(&self) -> u8 { self.0 & 0x0f }
+
+    /// Whether the telegram was repeated
+    pub fn is_repeated(&self) -> bool { self.repeater_count() != 0 }
+
+    /// NU flag of RPS telegrams : N-message (1) or U-message (0)
+    pub fn is_nu(&self) -> bool { self.0 & 0x10 != 0 }
+
+    /// T21 flag of RPS telegrams : PTM type 2 (1) or PTM type 1 (0)
+    pub fn t21(&self) -> bool { self.0 & 0x20 != 0 }
+}
+
+impl From<u8> for Status {
+    fn from(status: u8) -> Self { Status(status) }
+}
+
+impl From<Status> for u8 {
+    fn from(status: Status) -> Self { status.0 }
+}
 
 #[derive(Debug,Error)]
 pub enum ParseError {
@@ -30,7 +100,21 @@ pub enum SubtelNum {
     Receive = 0,
 }
 
-#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+impl SubtelNum {
+    /// Tell a telegram to send from a received one, from its ERP1 optional data.
+    ///
+    /// A telegram to send has 3 as subtel num and 0xFF as dBm.
+    /// Received telegrams give their number of subtelegrams (which may also be 3) and the best RSSI.
+    pub fn of_optional_data(subtel_num: u8, dbm: u8) -> SubtelNum {
+        match (subtel_num, dbm) {
+            (3, 0xff) => SubtelNum::Send,
+            _ => SubtelNum::Receive,
+        }
+    }
+}
+
+#[derive(Debug,Clone,Copy,PartialEq,Eq,TryFromPrimitive)]
+#[repr(u8)]
 pub enum Security {
     None = 0,
     Obsolete = 1,
@@ -44,34 +128,58 @@ pub struct RadioErp1<'a> {
     pub choice: u8,
     pub user_data: &'a [u8],
     pub sender_id: Address,
-    pub status: u8,
+    pub status: Status,
     pub subtel_num: Option<SubtelNum>,
     pub destination: Option<Address>,
     pub rssi: Option<u8>,
     pub security: Option<Security>
 }
 
+/// Timing and signal strength of one of the subtelegrams of a RADIO_SUB_TEL packet
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct SubTelegram {
+    /// Reception time, relative to the timestamp of the packet [ms]
+    pub tick: u8,
+    /// Signal strength, the magnitude of a negative dBm value
+    pub rssi: u8,
+    pub status: Status,
+}
+
+/// A radio telegram with the details of its received subtelegrams (packet type 0x03), for diagnostics
+#[derive(Debug,Clone)]
+pub struct RadioSubTel<'a> {
+    /// The telegram, with its ERP1 optional data (subtel num, destination, best dBm and security level)
+    pub telegram: RadioErp1<'a>,
+    /// Lower 16 bits of the system timer when the telegram was received [ms]
+    pub timestamp: u16,
+    pub subtelegrams: Vec<SubTelegram>,
+}
+
 #[derive(Debug,Clone,Copy)]
 // TODO parse details
 pub enum Event<'a> {
     SAReclaimUnsuccessful,
-    SAConfirmLearn       { data: &'a [u8; 17] }, 
+    SAConfirmLearn       { data: &'a [u8; 16] }, 
     SALearnAck           { data: &'a [u8; 3]},
     COReady              { wakeup: u8, mode: Option<u8> },
     COEventSecureDevices { cause: u8, device: Address },
+    /// Cause 1 : the duty cycle limit is reached, no more telegram will be sent. 0 : sending is possible again
     CODutyCycleLimit     { cause: u8},
     COTXFailed           { cause: u8},
     COTXDone,
     COLrnModeDisabled,
+
+    Unknown { code: u8, data: &'a [u8], optional: &'a [u8] }
 }
 
-#[derive(Debug,Clone)]
+#[derive(Debug,Clone,PartialEq)]
 pub struct Response {
     pub code: ResponseCode,
     pub data: Vec<u8>,
+    pub optional: Vec<u8>,
 }
 
-#[derive(Debug,Clone,Copy)]
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
 pub struct Version {
     pub main: u8,
     pub beta: u8,
@@ -79,7 +187,37 @@ pub struct Version {
     pub build: u8,
 }
 
-#[derive(Debug,Clone)]
+/// Response to CO_RD_REPEATER
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct RepeaterResponse {
+    pub enabled: bool,
+    /// 1 or 2, 0 when disabled
+    pub level: u8,
+}
+
+/// Response to CO_GET_DUTYCYCLE_LIMIT
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct DutyCycleResponse {
+    /// Duty cycle available for the current slot [%]
+    pub available: u8,
+    /// Number of slots of the duty cycle period
+    pub slots: u8,
+    /// Length of a slot [s]
+    pub slot_period: u16,
+    /// Time left in the current slot [s]
+    pub slot_left: u16,
+    /// Duty cycle available when the next slot begins [%]
+    pub load_after_actual: u8,
+}
+
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct BaseIdResponse {
+    pub base_id: Address,
+    /// Remaining write cycles for the base ID, if provided by the gateway
+    pub remaining_writes: Option<u8>,
+}
+
+#[derive(Debug,Clone,PartialEq)]
 pub struct VersionResponse {
     pub app: Version,
     pub api: Version,
@@ -90,64 +228,350 @@ pub struct VersionResponse {
 
 #[derive(Debug,Clone,Copy)]
 pub enum CommonCommand<'a> {
-    //Reset,
+    Reset,
     ReadVersion,
+    ReadIdBase,
+    WriteIdBase { base: Address },
+    /// CO_WR_REPEATER : `level` is 1 or 2 when enabled, and written as 0 when disabled
+    WriteRepeater { enabled: bool, level: u8 },
+    /// CO_RD_REPEATER : the response is decoded by `RepeaterResponse`
+    ReadRepeater,
+    /// CO_WR_LEARNMODE : enable/disable the learn mode of the controller, for `timeout` ms (`None` is sent as 0, the default timeout)
+    WriteLearnMode { enabled: bool, timeout: Option<u32> },
+    /// CO_GET_DUTYCYCLE_LIMIT (sub-1GHz gateways) : the response is decoded by `DutyCycleResponse`
+    ReadDutyCycleLimit,
     //ReadSystemLog,
 
     Unknown { code: u8, data: &'a [u8], optional: &'a [u8] }
 }
 
+/// Smart Ack commands (packet type 0x06)
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum SmartAck<'a> {
+    /// SA_WR_LEARNMODE : enable/disable learn mode for `timeout` ms (0 : default timeout).
+    /// `extended` : 0 for simple learn mode, 1 for advanced learn mode, 2 for advanced learn mode with repeater selection.
+    WriteLearnMode { enable: bool, extended: u8, timeout: u32 },
+    /// SA_RD_LEARNEDCLIENTS : the response lists the learned clients, see `LearnedClient`
+    ReadLearnedClients,
+    /// SA_WR_RESET : reset a learned client
+    Reset { client: Address },
+
+    Unknown { code: u8, data: &'a [u8], optional: &'a [u8] }
+}
+
+/// Manufacturer ID of remote management commands which are not manufacturer specific
+pub const REMAN_MULTI_USER_MANUFACTURER: u16 = 0x7ff;
+
+/// Remote management command (packet type 0x07), sent over the air to a remote device
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct RemoteManagement {
+    /// Function number (12 bits), eg. 0x004 for "query ID"
+    pub function: u16,
+    /// Manufacturer ID (11 bits)
+    pub manufacturer: u16,
+    /// Message data, depending on the function
+    pub data: Vec<u8>,
+    pub destination: Address,
+    /// Source ID, `0x00000000` to use the ID of the gateway
+    pub source: Address,
+    /// Send the command with a random delay, as needed for broadcast queries
+    pub send_with_delay: bool,
+}
+
+/// A Smart Ack client learned by the gateway, as returned by SA_RD_LEARNEDCLIENTS
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct LearnedClient {
+    pub client: Address,
+    pub controller: Address,
+    pub mailbox_index: u8,
+}
+
 #[derive(Debug,Clone)]
 pub enum Packet<'a> {
-    //RadioErp1(RadioErp1<'a>),
+    RadioErp1(RadioErp1<'a>),
     Response(Response),
-    //Event(Event<'a>),
+    Event(Event<'a>),
     CommonCommand(CommonCommand<'a>),
-    //SmartAck,
-    //RemoteMan,
+    SmartAck(SmartAck<'a>),
+    RemoteManagement(RemoteManagement),
     //RadioMessage,
     //RadioErp2,
     //CommandAccepted,
     //RadioLRWPAN,
     //Command24GHz,
 
+    RadioSubTel(RadioSubTel<'a>),
+
     Unknown { packet_type: u8, data: &'a [u8], optional: &'a [u8] }
-    //RadioSubTel(RadioSubTel),
+}
+
+impl<'a> RadioErp1<'a> {
+
+    /// Received signal strength in dBm (eg. -55). The raw `rssi` byte is the magnitude of this negative value.
+    /// `None` for a telegram to send, which has no signal strength.
+    pub fn rssi_dbm(&self) -> Option<i16> {
+        match self.subtel_num {
+            Some(SubtelNum::Send) if self.rssi == Some(0xff) => None,
+            _ => self.rssi.map(|rssi| -(rssi as i16)),
+        }
+    }
+
+    /// Build the ERP1 frame (packet type 0x01) of this telegram, eg. to send it.
+    /// Optional data fields are written in order, up to the first `None` one.
+    pub fn encode(&self) -> ESP3Frame {
+        let mut data = Vec::with_capacity(self.user_data.len() + 6);
+        data.push(self.choice);
+        data.extend_from_slice(self.user_data);
+        data.extend_from_slice(&self.sender_id.0);
+        data.push(self.status.0);
+
+        let mut optional = Vec::with_capacity(7);
+        if let Some(subtel_num) = self.subtel_num {
+            optional.push(subtel_num as u8);
+            if let Some(destination) = self.destination {
+                optional.extend_from_slice(&destination.0);
+                if let Some(rssi) = self.rssi {
+                    optional.push(rssi);
+                    optional.extend(self.security.map(|security| security as u8));
+                }
+            }
+        }
+        ESP3Frame::assemble(0x01, &data, &optional)
+    }
+
+    /// Decode an ERP1 radio telegram. Data is `choice | user data | sender id (4) | status`,
+    /// optional data is `subtel num | destination (4) | dBm | security level`, each field being optional.
+    pub fn decode(frame: ESP3FrameRef<'a>) -> Result<Self, ParseError> {
+        let d = frame.data;
+        if d.len() < 6 {
+            return Err(ParseError::PacketTooShort)
+        }
+        let n = d.len();
+        let o = frame.optional_data;
+
+        Ok(Self {
+            choice: d[0],
+            user_data: &d[1..n-5],
+            sender_id: Address(d[n-5..n-1].try_into().unwrap()),
+            status: Status(d[n-1]),
+            subtel_num: o.first().map(|&b| SubtelNum::of_optional_data(b, o.get(5).copied().unwrap_or(0xff))),
+            destination: o.get(1..5).map(|id| Address(id.try_into().unwrap())),
+            rssi: o.get(5).copied(),
+            security: o.get(6).and_then(|&b| Security::try_from_primitive(b).ok()),
+        })
+    }
+}
+
+impl<'a> RadioSubTel<'a> {
+
+    /// Number of subtelegrams received
+    pub fn subtel_count(&self) -> usize {
+        self.subtelegrams.len()
+    }
+
+    /// Build the RADIO_SUB_TEL frame (packet type 0x03) of this telegram.
+    /// The ERP1 optional data is always written, with the values used to send a telegram for absent fields.
+    pub fn encode(&self) -> ESP3Frame {
+        let telegram = &self.telegram;
+        let erp1 = telegram.encode();
+        let mut optional = Vec::with_capacity(9 + 3 * self.subtelegrams.len());
+        optional.push(match telegram.subtel_num {
+            // The subtel num of a received telegram is its number of subtelegrams
+            Some(SubtelNum::Receive) => self.subtelegrams.len() as u8,
+            Some(SubtelNum::Send) | None => SubtelNum::Send as u8,
+        });
+        optional.extend_from_slice(&telegram.destination.unwrap_or(BROADCAST).0);
+        optional.push(telegram.rssi.unwrap_or(0xff));
+        optional.push(telegram.security.unwrap_or(Security::None) as u8);
+        optional.extend_from_slice(&self.timestamp.to_be_bytes());
+        for subtelegram in &self.subtelegrams {
+            optional.extend_from_slice(&[subtelegram.tick, subtelegram.rssi, subtelegram.status.0]);
+        }
+        ESP3Frame::assemble(0x03, erp1.data(), &optional)
+    }
+
+    /// Decode a RADIO_SUB_TEL packet. Data is the same as ERP1,
+    /// optional data is `subtel num | destination (4) | dBm | security level | timestamp (2)`
+    /// followed by `tick | dBm | status` for each subtelegram.
+    pub fn decode(frame: ESP3FrameRef<'a>) -> Result<Self, ParseError> {
+        let o = frame.optional_data;
+        if o.len() < 9 {
+            return Err(ParseError::PacketTooShort)
+        }
+        let telegram = RadioErp1::decode(ESP3FrameRef { optional_data: &o[..7], ..frame })?;
+        let subtelegrams = o[9..].chunks_exact(3)
+            .map(|sub| SubTelegram { tick: sub[0], rssi: sub[1], status: Status(sub[2]) })
+            .collect();
+
+        Ok(Self {
+            telegram,
+            timestamp: u16::from_be_bytes([o[7], o[8]]),
+            subtelegrams,
+        })
+    }
+}
+
+impl<'a> Event<'a> {
+
+    /// For a `CODutyCycleLimit` event, whether the limit is reached (`Some(false)` once it is released)
+    pub fn duty_cycle_limit_reached(&self) -> Option<bool> {
+        match self {
+            Self::CODutyCycleLimit { cause } => Some(*cause == 1),
+            _ => None,
+        }
+    }
+
+    fn assemble(code: u8, data: &[u8], optional: &[u8]) -> ESP3Frame {
+        let packet_type = 0x04;
+        let mut frame_data = vec![code];
+        frame_data.extend_from_slice(data);
+        ESP3Frame::assemble(packet_type, &frame_data, optional)
+    }
+
+    /// Build the event frame (packet type 0x04) : the event code followed by its payload.
+    pub fn encode(&self) -> ESP3Frame {
+        match *self {
+            Self::SAReclaimUnsuccessful => Self::assemble(0x01, &[], &[]),
+            Self::SAConfirmLearn { data } => Self::assemble(0x02, data, &[]),
+            Self::SALearnAck { data } => Self::assemble(0x03, data, &[]),
+            Self::COReady { wakeup, mode } => Self::assemble(0x04, &[wakeup], mode.as_slice()),
+            Self::COEventSecureDevices { cause, device } => {
+                let mut data = vec![cause];
+                data.extend_from_slice(&device.0);
+                Self::assemble(0x05, &data, &[])
+            }
+            Self::CODutyCycleLimit { cause } => Self::assemble(0x06, &[cause], &[]),
+            Self::COTXFailed { cause } => Self::assemble(0x07, &[cause], &[]),
+            Self::COTXDone => Self::assemble(0x08, &[], &[]),
+            Self::COLrnModeDisabled => Self::assemble(0x09, &[], &[]),
+            Self::Unknown { code, data, optional } => Self::assemble(code, data, optional),
+        }
+    }
+
+    /// Decode an event packet. The first data byte is the event code, the rest depends on it.
+    pub fn decode(frame: ESP3FrameRef<'a>) -> Result<Self, ParseError> {
+        let (&code, d) = frame.data.split_first().ok_or(ParseError::PacketTooShort)?;
+        let byte = |i: usize| d.get(i).copied().ok_or(ParseError::PacketTooShort);
+
+        Ok(match code {
+            0x01 => Self::SAReclaimUnsuccessful,
+            0x02 => Self::SAConfirmLearn { data: d.get(..16).ok_or(ParseError::PacketTooShort)?.try_into().unwrap() },
+            0x03 => Self::SALearnAck { data: d.get(..3).ok_or(ParseError::PacketTooShort)?.try_into().unwrap() },
+            0x04 => Self::COReady { wakeup: byte(0)?, mode: frame.optional_data.first().copied() },
+            0x05 => Self::COEventSecureDevices {
+                cause: byte(0)?,
+                device: Address(d.get(1..5).ok_or(ParseError::PacketTooShort)?.try_into().unwrap()),
+            },
+            0x06 => Self::CODutyCycleLimit { cause: byte(0)? },
+            0x07 => Self::COTXFailed { cause: byte(0)? },
+            0x08 => Self::COTXDone,
+            0x09 => Self::COLrnModeDisabled,
+            _    => Self::Unknown { code, data: d, optional: frame.optional_data },
+        })
+    }
 }
 
 impl VersionResponse {
+    /// Build the 32 bytes response payload. The description is truncated or zero-padded to 16 bytes.
     pub fn encode(&self) -> Response {
-        todo!();
+        let mut data = Vec::with_capacity(32);
+        for v in [&self.app, &self.api] {
+            data.extend_from_slice(&[v.main, v.beta, v.alpha, v.build]);
+        }
+        data.extend_from_slice(&self.chip_id.0);
+        data.extend_from_slice(&self.chip_version);
+
+        let mut description = [0; 16];
+        let bytes = self.description.as_bytes();
+        let len = bytes.len().min(16);
+        description[..len].copy_from_slice(&bytes[..len]);
+        data.extend_from_slice(&description);
+
+        Response { code: ResponseCode::Ok, data, optional: vec![] }
     }
 
+    /// Decode a response payload. The description is usually 16 bytes, zero-padded,
+    /// but any length is accepted : it ends at the first NUL byte, or at the end of the payload.
+    ///
+    /// Invalid UTF-8 bytes of the description are replaced by `U+FFFD`, use `decode_strict` to reject them.
     pub fn decode(response: &Response) -> Result<Self, ParseError> {
+        Self::decode_with(response, |description| Ok(String::from_utf8_lossy(description).into_owned()))
+    }
+
+    /// Same as `decode`, but fails with `ParseError::UTF8` if the description is not valid UTF-8
+    pub fn decode_strict(response: &Response) -> Result<Self, ParseError> {
+        Self::decode_with(response, |description| Ok(std::str::from_utf8(description)?.to_owned()))
+    }
+
+    fn decode_with(response: &Response, description_of: impl Fn(&[u8]) -> Result<String, ParseError>) -> Result<Self, ParseError> {
         let d = &response.data;
-        if d.len() != 32 {
+        if d.len() < 16 {
             return Err(ParseError::PacketTooShort)
         }
+        let description = &d[16..];
+        let description = match description.iter().position(|&b| b == 0) {
+            Some(end) => &description[..end],
+            None => description,
+        };
 
         Ok(Self {
             app: Version { main: d[0], beta: d[1], alpha: d[2], build: d[3] },
             api: Version { main: d[4], beta: d[5], alpha: d[6], build: d[7] },
             chip_id: Address(d[8..12].try_into().unwrap()),
             chip_version: d[12..16].try_into().unwrap(),
-            description: std::str::from_utf8(&d[16..32])?.to_owned(),
+            description: description_of(description)?,
         })
 
     }
 }
 
+impl BaseIdResponse {
+    pub fn decode(response: &Response) -> Result<Self, ParseError> {
+        let base_id = response.data.get(0..4).ok_or(ParseError::PacketTooShort)?;
+        Ok(Self {
+            base_id: Address(base_id.try_into().unwrap()),
+            remaining_writes: response.optional.first().copied(),
+        })
+    }
+}
+
+impl DutyCycleResponse {
+    /// Decode the response payload : available duty cycle, slots, slot period, slot left, load after actual
+    pub fn decode(response: &Response) -> Result<Self, ParseError> {
+        let d = response.data.get(0..7).ok_or(ParseError::PacketTooShort)?;
+        Ok(Self {
+            available: d[0],
+            slots: d[1],
+            slot_period: u16::from_be_bytes([d[2], d[3]]),
+            slot_left: u16::from_be_bytes([d[4], d[5]]),
+            load_after_actual: d[6],
+        })
+    }
+}
+
+impl RepeaterResponse {
+    /// Decode the response payload : REP_ENABLE, REP_LEVEL
+    pub fn decode(response: &Response) -> Result<Self, ParseError> {
+        let d = response.data.get(0..2).ok_or(ParseError::PacketTooShort)?;
+        Ok(Self { enabled: d[0] != 0, level: d[1] })
+    }
+}
+
 impl Response {
 
     pub fn encode(&self) -> ESP3Frame {
-        todo!()
+        let mut frame_data = vec![self.code.into()];
+        frame_data.extend_from_slice(&self.data);
+        ESP3Frame::assemble(0x02, &frame_data, &self.optional)
     }
 
     pub fn decode(frame: ESP3FrameRef) -> Result<Self, ParseError> {
-        let code = ResponseCode::try_from_primitive(frame.data[0])
-            .map_err(|_| ParseError::InvalidResultCode(frame.data[0]))?;
-        let data = frame.data[1..].into();
-        Ok( Self { code, data })
+        let (&code, data) = frame.data.split_first().ok_or(ParseError::PacketTooShort)?;
+        let code = ResponseCode::try_from_primitive(code)
+            .map_err(|_| ParseError::InvalidResultCode(code))?;
+        let data = data.into();
+        let optional = frame.optional_data.into();
+        Ok( Self { code, data, optional })
     }
 
 }
@@ -164,28 +588,628 @@ impl<'a> CommonCommand<'a> {
     fn encode(&self) -> ESP3Frame {
         match self {
             &Self::Unknown { code, data, optional } => CommonCommand::assemble(code, data, optional),
+            &Self::Reset => CommonCommand::assemble(0x02, &[], &[]),
             &Self::ReadVersion => CommonCommand::assemble(0x03, &[], &[]),
+            &Self::ReadIdBase => CommonCommand::assemble(0x08, &[], &[]),
+            &Self::WriteIdBase { base } => CommonCommand::assemble(0x07, &base.0, &[]),
+            &Self::WriteRepeater { enabled, level } => {
+                CommonCommand::assemble(0x09, &[enabled as u8, if enabled { level } else { 0 }], &[])
+            }
+            &Self::ReadRepeater => CommonCommand::assemble(0x0a, &[], &[]),
+            &Self::WriteLearnMode { enabled, timeout } => {
+                let mut data = vec![enabled as u8];
+                data.extend_from_slice(&timeout.unwrap_or(0).to_be_bytes());
+                CommonCommand::assemble(0x17, &data, &[])
+            }
+            &Self::ReadDutyCycleLimit => CommonCommand::assemble(0x23, &[], &[]),
+        }
+    }
+}
+
+impl<'a> SmartAck<'a> {
+
+    fn assemble(code: u8, data: &[u8], optional: &[u8]) -> ESP3Frame {
+        let packet_type = 0x06;
+        let mut frame_data = vec![code];
+        frame_data.extend_from_slice(data);
+        ESP3Frame::assemble(packet_type, &frame_data, optional)
+    }
+
+    fn encode(&self) -> ESP3Frame {
+        match *self {
+            Self::Unknown { code, data, optional } => SmartAck::assemble(code, data, optional),
+            Self::WriteLearnMode { enable, extended, timeout } => {
+                let mut data = vec![enable as u8, extended];
+                data.extend_from_slice(&timeout.to_be_bytes());
+                SmartAck::assemble(0x01, &data, &[])
+            }
+            Self::ReadLearnedClients => SmartAck::assemble(0x06, &[], &[]),
+            Self::Reset { client } => SmartAck::assemble(0x05, &client.0, &[]),
         }
     }
+
+    /// Decode a Smart Ack command. The first data byte is the command code, the rest depends on it.
+    pub fn decode(frame: ESP3FrameRef<'a>) -> Result<Self, ParseError> {
+        let (&code, d) = frame.data.split_first().ok_or(ParseError::PacketTooShort)?;
+
+        Ok(match code {
+            0x01 => {
+                let d = d.get(..6).ok_or(ParseError::PacketTooShort)?;
+                Self::WriteLearnMode {
+                    enable: d[0] != 0,
+                    extended: d[1],
+                    timeout: u32::from_be_bytes(d[2..6].try_into().unwrap()),
+                }
+            }
+            0x05 => Self::Reset { client: Address(d.get(..4).ok_or(ParseError::PacketTooShort)?.try_into().unwrap()) },
+            0x06 => Self::ReadLearnedClients,
+            _    => Self::Unknown { code, data: d, optional: frame.optional_data },
+        })
+    }
+}
+
+impl RemoteManagement {
+    /// "Query ID" (function 0x004) : devices matching `eep` (all devices if `None`) answer with their ID.
+    /// The EEP is sent as RORG (8 bits), FUNC (6 bits) and TYPE (7 bits), followed by 3 unused bits.
+    pub fn query_id(destination: Address, eep: Option<(u8, u8, u8)>) -> Self {
+        let data = match eep {
+            Some((rorg, func, eep_type)) => {
+                let bits = (rorg as u32) << 16 | ((func & 0x3f) as u32) << 10 | ((eep_type & 0x7f) as u32) << 3;
+                bits.to_be_bytes()[1..].to_vec()
+            }
+            None => vec![],
+        };
+        RemoteManagement::multi_user(0x004, data, destination, destination == BROADCAST)
+    }
+
+    /// "Action command" (function 0x005) : the device performs its identification action (eg. blinks)
+    pub fn action(destination: Address) -> Self {
+        RemoteManagement::multi_user(0x005, vec![], destination, false)
+    }
+
+    fn multi_user(function: u16, data: Vec<u8>, destination: Address, send_with_delay: bool) -> Self {
+        RemoteManagement {
+            function,
+            manufacturer: REMAN_MULTI_USER_MANUFACTURER,
+            data,
+            destination,
+            source: Address([0; 4]),
+            send_with_delay,
+        }
+    }
+
+    /// Data : function (2 bytes), manufacturer (2 bytes) and message data.
+    /// Optional data : destination (4 bytes), source (4 bytes), dBm (0xff when sending) and send with delay.
+    pub fn encode(&self) -> ESP3Frame {
+        let mut data = Vec::with_capacity(4 + self.data.len());
+        data.extend_from_slice(&(self.function & 0x0fff).to_be_bytes());
+        data.extend_from_slice(&(self.manufacturer & 0x07ff).to_be_bytes());
+        data.extend_from_slice(&self.data);
+
+        let mut optional = Vec::with_capacity(10);
+        optional.extend_from_slice(&self.destination.0);
+        optional.extend_from_slice(&self.source.0);
+        optional.extend_from_slice(&[0xff, self.send_with_delay as u8]);
+
+        ESP3Frame::assemble(0x07, &data, &optional)
+    }
+}
+
+impl LearnedClient {
+    /// Decode the response to SA_RD_LEARNEDCLIENTS : 9 bytes per client
+    pub fn decode_all(response: &Response) -> Result<Vec<Self>, ParseError> {
+        let clients = response.data.chunks_exact(9);
+        if !clients.remainder().is_empty() {
+            return Err(ParseError::PacketTooShort)
+        }
+        Ok(clients.map(|d| Self {
+            client: Address(d[0..4].try_into().unwrap()),
+            controller: Address(d[4..8].try_into().unwrap()),
+            mailbox_index: d[8],
+        }).collect())
+    }
 }
 
 impl<'a> Packet<'a> {
     pub fn encode(&self) -> ESP3Frame {
 
         use Packet::*;
-        match &self {
-            &CommonCommand(cmd) => cmd.encode(),
-            &Response(resp) => resp.encode(),
-            &Unknown { packet_type, data, optional } => ESP3Frame::assemble(*packet_type, data, optional),
+        match self {
+            RadioErp1(erp1) => erp1.encode(),
+            Event(event) => event.encode(),
+            CommonCommand(cmd) => cmd.encode(),
+            SmartAck(cmd) => cmd.encode(),
+            RemoteManagement(cmd) => cmd.encode(),
+            RadioSubTel(subtel) => subtel.encode(),
+            Response(resp) => resp.encode(),
+            Unknown { packet_type, data, optional } => ESP3Frame::assemble(*packet_type, data, optional),
         }       
     }
 
     pub fn decode(frame: ESP3FrameRef<'a>) -> Result<Self, ParseError> {
         match frame.packet_type {
+            0x01 => Ok(Self::RadioErp1(RadioErp1::decode(frame)?)),
             0x02 => Ok(Self::Response(Response::decode(frame)?)),
+            0x03 => Ok(Self::RadioSubTel(RadioSubTel::decode(frame)?)),
+            0x04 => Ok(Self::Event(Event::decode(frame)?)),
+            0x06 => Ok(Self::SmartAck(SmartAck::decode(frame)?)),
             _    => Err(ParseError::UnsupportedPacketType),
         }
     }
 
 }
 
+/// Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::borrow::Borrow;
+    use crate::crc8::compute_crc8;
+
+    // ERP1 decoding
+    // -------------------------------------------------------------------
+    #[test]
+    fn given_valid_f60201_frame_then_decode_radio_erp1() {
+        // F60201 when pushed
+        let received_message = vec![
+            85, 0, 7, 7, 1, 122, 246, 112, 254, 245, 143, 245, 48, 1, 255, 255, 255, 255, 46, 0, 249,
+        ];
+        let frame = ESP3Frame::read_from(&mut &received_message[..]).unwrap();
+        let erp1 = match Packet::decode(frame.as_ref()).unwrap() {
+            Packet::RadioErp1(erp1) => erp1,
+            p => panic!("Expected a RadioErp1 packet, got {:?}", p),
+        };
+        assert_eq!(erp1.choice, 0xf6);
+        assert_eq!(erp1.user_data, &[112]);
+        assert_eq!(erp1.sender_id, Address([254, 245, 143, 245]));
+        assert_eq!(erp1.status, Status(48));
+        assert!(erp1.status.t21() && erp1.status.is_nu());
+        assert!(!erp1.status.is_repeated());
+        assert_eq!(erp1.subtel_num, Some(SubtelNum::Receive));
+        assert_eq!(erp1.destination, Some(BROADCAST));
+        assert_eq!(erp1.rssi, Some(46));
+        assert_eq!(erp1.rssi_dbm(), Some(-46));
+        assert_eq!(erp1.security, Some(Security::None));
+    }
+
+    #[test]
+    fn given_erp1_frame_received_as_3_subtelegrams_then_decode_it_as_received() {
+        // F60201 when pushed, received as 3 subtelegrams at -46 dBm
+        let data = [246, 112, 254, 245, 143, 245, 48];
+        let frame = ESP3Frame::assemble(0x01, &data, &[3, 255, 255, 255, 255, 46, 0]);
+        let erp1 = RadioErp1::decode(frame.as_ref()).unwrap();
+        assert_eq!(erp1.subtel_num, Some(SubtelNum::Receive));
+        assert_eq!(erp1.rssi_dbm(), Some(-46));
+
+        // 3 subtelegrams and 0xFF as dBm is a telegram to send
+        let frame = ESP3Frame::assemble(0x01, &data, &[3, 255, 255, 255, 255, 0xff, 0]);
+        let erp1 = RadioErp1::decode(frame.as_ref()).unwrap();
+        assert_eq!(erp1.subtel_num, Some(SubtelNum::Send));
+        assert_eq!(erp1.rssi_dbm(), None);
+    }
+
+    #[test]
+    fn given_radio_sub_tel_frame_then_decode_its_subtelegrams() {
+        // F60201 when pushed, received as 3 subtelegrams at -46, -52 and -49 dBm (the 3rd one repeated)
+        let data = [246, 112, 254, 245, 143, 245, 48];
+        let optional = [3, 255, 255, 255, 255, 46, 0, 0x12, 0x34, 0, 46, 48, 8, 52, 48, 20, 49, 49];
+        let frame = ESP3Frame::assemble(0x03, &data, &optional);
+
+        let subtel = match Packet::decode(frame.as_ref()).unwrap() {
+            Packet::RadioSubTel(subtel) => subtel,
+            p => panic!("Expected a RadioSubTel packet, got {:?}", p),
+        };
+        assert_eq!(subtel.telegram.user_data, &[112]);
+        assert_eq!(subtel.telegram.sender_id, Address([254, 245, 143, 245]));
+        assert_eq!(subtel.telegram.rssi_dbm(), Some(-46));
+        assert_eq!(subtel.telegram.subtel_num, Some(SubtelNum::Receive));
+        assert_eq!(subtel.timestamp, 0x1234);
+        assert_eq!(subtel.subtel_count(), 3);
+        assert_eq!(subtel.subtelegrams[1], SubTelegram { tick: 8, rssi: 52, status: Status(48) });
+        assert!(subtel.subtelegrams[2].status.is_repeated());
+        assert_eq!(subtel.encode().as_ref().optional_data, &optional);
+
+        // Absent ERP1 optional fields are written with their default values
+        let without_optional = RadioSubTel {
+            telegram: RadioErp1 { subtel_num: None, destination: None, rssi: None, security: None, ..subtel.telegram },
+            ..subtel.clone()
+        };
+        let frame = without_optional.encode();
+        assert_eq!(&frame.optional_data()[..9], &[3, 255, 255, 255, 255, 255, 0, 0x12, 0x34]);
+        match Packet::decode(frame.as_ref()).unwrap() {
+            Packet::RadioSubTel(decoded) => {
+                assert_eq!(decoded.timestamp, 0x1234);
+                assert_eq!(decoded.subtelegrams, subtel.subtelegrams);
+            }
+            p => panic!("Expected a RadioSubTel packet, got {:?}", p),
+        }
+
+        let truncated = ESP3Frame::assemble(0x03, &data, &optional[..7]);
+        assert!(matches!(Packet::decode(truncated.as_ref()), Err(ParseError::PacketTooShort)));
+    }
+
+    #[test]
+    fn given_valid_a50401_frame_then_decode_radio_erp1_user_data() {
+        // A50401 when button is not pushed (automatic send from trh)
+        let received_message = vec![
+            85, 0, 10, 7, 1, 235, 165, 0, 229, 204, 10, 5, 17, 114, 247, 0, 1, 255, 255, 255, 255,
+            54, 0, 213,
+        ];
+        let frame = ESP3Frame::read_from(&mut &received_message[..]).unwrap();
+        let erp1 = RadioErp1::decode(frame.as_ref()).unwrap();
+        assert_eq!(erp1.choice, 0xa5);
+        assert_eq!(erp1.user_data, &[0, 229, 204, 10]);
+        assert_eq!(erp1.sender_id, Address([5, 17, 114, 247]));
+        assert_eq!(erp1.status, Status(0));
+    }
+
+    #[test]
+    fn given_repeater_commands_then_encode_them_and_decode_the_response() {
+        let frame = Packet::CommonCommand(CommonCommand::WriteRepeater { enabled: true, level: 2 }).encode();
+        assert_eq!((frame.packet_type(), frame.data()), (0x05, &[0x09, 0x01, 0x02][..]));
+        let frame = Packet::CommonCommand(CommonCommand::WriteRepeater { enabled: false, level: 2 }).encode();
+        assert_eq!(frame.data(), &[0x09, 0x00, 0x00]);
+        let frame = Packet::CommonCommand(CommonCommand::ReadRepeater).encode();
+        assert_eq!(frame.data(), &[0x0a]);
+
+        let response = Response::decode(ESP3Frame::assemble(0x02, &[0x00, 0x01, 0x01], &[]).as_ref()).unwrap();
+        assert_eq!(RepeaterResponse::decode(&response).unwrap(), RepeaterResponse { enabled: true, level: 1 });
+        let response = Response { code: ResponseCode::Ok, data: vec![0x01], optional: vec![] };
+        assert!(matches!(RepeaterResponse::decode(&response), Err(ParseError::PacketTooShort)));
+    }
+
+    #[test]
+    fn given_learn_mode_command_then_encode_the_timeout_big_endian() {
+        let frame = Packet::CommonCommand(CommonCommand::WriteLearnMode { enabled: true, timeout: Some(60_000) }).encode();
+        assert_eq!((frame.packet_type(), frame.data()), (0x05, &[0x17, 0x01, 0x00, 0x00, 0xea, 0x60][..]));
+        let frame = Packet::CommonCommand(CommonCommand::WriteLearnMode { enabled: false, timeout: None }).encode();
+        assert_eq!(frame.data(), &[0x17, 0x00, 0x00, 0x00, 0x00, 0x00]);
+    }
+
+    #[test]
+    fn given_remote_management_commands_then_encode_them() {
+        let frame = RemoteManagement::query_id(BROADCAST, Some((0xa5, 0x02, 0x05))).encode();
+        assert_eq!(frame.packet_type(), 0x07);
+        assert_eq!(frame.data(), &[0x00, 0x04, 0x07, 0xff, 0xa5, 0x08, 0x28]);
+        assert_eq!(frame.optional_data(), &[0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00, 0xff, 0x01]);
+
+        let frame = Packet::RemoteManagement(RemoteManagement::action(Address([0x01, 0x85, 0x6a, 0x21]))).encode();
+        let bytes: &[u8] = frame.borrow();
+        assert_eq!(
+            bytes,
+            &[0x55, 0x00, 0x04, 0x0a, 0x07, compute_crc8(&[0x00, 0x04, 0x0a, 0x07]),
+              0x00, 0x05, 0x07, 0xff, 0x01, 0x85, 0x6a, 0x21, 0x00, 0x00, 0x00, 0x00, 0xff, 0x00,
+              compute_crc8(&[0x00, 0x05, 0x07, 0xff, 0x01, 0x85, 0x6a, 0x21, 0x00, 0x00, 0x00, 0x00, 0xff, 0x00])]
+        );
+    }
+
+    #[test]
+    fn given_radio_erp1_frame_without_optional_data_then_optional_fields_are_none() {
+        let frame = ESP3Frame::assemble(0x01, &[246, 112, 254, 245, 143, 245, 48], &[]);
+        let erp1 = RadioErp1::decode(frame.as_ref()).unwrap();
+        assert_eq!(erp1.sender_id, Address([254, 245, 143, 245]));
+        assert!(erp1.subtel_num.is_none());
+        assert!(erp1.destination.is_none());
+        assert!(erp1.rssi.is_none());
+        assert!(erp1.security.is_none());
+    }
+
+    #[test]
+    fn given_radio_erp1_then_encode_same_frame_as_create_f60201_telegram() {
+        let erp1 = RadioErp1 {
+            choice: 0xf6,
+            user_data: &[0x10],
+            sender_id: Address([0, 0, 0, 0]),
+            status: Status(0x30),
+            subtel_num: Some(SubtelNum::Send),
+            destination: Some(BROADCAST),
+            rssi: Some(0xff),
+            security: Some(Security::None),
+        };
+        let telegram = crate::eep::create_f60201_telegram(crate::eep::F602EmulateCommand::MoveBlindClosed).unwrap();
+
+        let frame = Packet::RadioErp1(erp1).encode();
+        let bytes: &[u8] = std::borrow::Borrow::borrow(&frame);
+        assert_eq!(bytes, &Vec::from(&telegram)[..]);
+
+        // And decode it back
+        let decoded = RadioErp1::decode(frame.as_ref()).unwrap();
+        assert_eq!(decoded.user_data, &[0x10]);
+        assert_eq!(decoded.destination, Some(BROADCAST));
+        assert_eq!(decoded.security, Some(Security::None));
+    }
+
+    #[test]
+    fn given_radio_erp1_without_optional_data_then_encode_data_only() {
+        let erp1 = RadioErp1 {
+            choice: 0xa5,
+            user_data: &[0, 229, 204, 10],
+            sender_id: Address([5, 17, 114, 247]),
+            status: Status(0),
+            subtel_num: None,
+            destination: Some(BROADCAST),
+            rssi: None,
+            security: None,
+        };
+        let frame = erp1.encode();
+        assert_eq!(frame.data(), &[0xa5, 0, 229, 204, 10, 5, 17, 114, 247, 0]);
+        assert!(frame.optional_data().is_empty());
+    }
+
+    #[test]
+    fn given_too_short_radio_erp1_frame_then_return_error() {
+        let frame = ESP3Frame::assemble(0x01, &[246, 112, 254, 245, 143], &[]);
+        assert!(matches!(RadioErp1::decode(frame.as_ref()), Err(ParseError::PacketTooShort)));
+    }
+
+    // Response encoding
+    // -------------------------------------------------------------------
+    #[test]
+    fn given_response_then_encode_and_decode_it_back() {
+        let response = Response { code: ResponseCode::Ok, data: vec![255, 155, 18, 128], optional: vec![10] };
+        let frame = response.encode();
+        assert_eq!(frame.packet_type(), 0x02);
+        assert_eq!(frame.data(), &[0, 255, 155, 18, 128]);
+        assert_eq!(frame.optional_data(), &[10]);
+        assert_eq!(Response::decode(frame.as_ref()).unwrap(), response);
+    }
+
+    #[test]
+    fn given_version_response_then_encode_and_decode_it_back() {
+        let version = VersionResponse {
+            app: Version { main: 2, beta: 11, alpha: 1, build: 0 },
+            api: Version { main: 2, beta: 6, alpha: 3, build: 0 },
+            chip_id: Address([0x04, 0x01, 0x93, 0x5e]),
+            chip_version: [0x45, 0x4f, 0x01, 0x03],
+            description: String::from("GATEWAYCTRL"),
+        };
+        let response = version.encode();
+        assert_eq!(response.data.len(), 32);
+        assert_eq!(VersionResponse::decode(&response).unwrap(), version);
+
+        let frame = response.encode();
+        let decoded = Response::decode(frame.as_ref()).unwrap();
+        assert_eq!(VersionResponse::decode(&decoded).unwrap(), version);
+    }
+
+    #[test]
+    fn given_version_response_with_long_description_then_truncate_it() {
+        let version = VersionResponse {
+            app: Version { main: 1, beta: 0, alpha: 0, build: 0 },
+            api: Version { main: 1, beta: 0, alpha: 0, build: 0 },
+            chip_id: BROADCAST,
+            chip_version: [0; 4],
+            description: String::from("A description longer than 16 bytes"),
+        };
+        let decoded = VersionResponse::decode(&version.encode()).unwrap();
+        assert_eq!(decoded.description, "A description lo");
+    }
+
+    #[test]
+    fn given_32_or_34_bytes_version_responses_then_decode_them() {
+        let mut data = vec![2, 11, 1, 0, 2, 6, 3, 0, 0x04, 0x01, 0x93, 0x5e, 0x45, 0x4f, 0x01, 0x03];
+        data.extend_from_slice(b"GATEWAYCTRL\0\0\0\0\0");
+        let response = Response { code: ResponseCode::Ok, data: data.clone(), optional: vec![] };
+        let version = VersionResponse::decode(&response).unwrap();
+        assert_eq!(version.description, "GATEWAYCTRL");
+        assert_eq!(version.chip_id, Address([0x04, 0x01, 0x93, 0x5e]));
+
+        // Longer payload, with optional data
+        data.extend_from_slice(&[0x00, 0x01]);
+        let response = Response { code: ResponseCode::Ok, data, optional: vec![0x01] };
+        assert_eq!(response.data.len(), 34);
+        assert_eq!(VersionResponse::decode(&response).unwrap(), version);
+
+        // Short description, and truncated payload
+        let response = Response { code: ResponseCode::Ok, data: [&response.data[..16], b"USB300"].concat(), optional: vec![] };
+        assert_eq!(VersionResponse::decode(&response).unwrap().description, "USB300");
+        let response = Response { code: ResponseCode::Ok, data: response.data[..15].to_vec(), optional: vec![] };
+        assert!(matches!(VersionResponse::decode(&response), Err(ParseError::PacketTooShort)));
+    }
+
+    #[test]
+    fn given_nul_padded_non_utf8_description_then_decode_it_lossily() {
+        let mut data = vec![2, 11, 1, 0, 2, 6, 3, 0, 0x04, 0x01, 0x93, 0x5e, 0x45, 0x4f, 0x01, 0x03];
+        data.extend_from_slice(b"GATEWAY\xffCTRL\0\0\0\0\xff");
+        let response = Response { code: ResponseCode::Ok, data, optional: vec![] };
+
+        assert_eq!(VersionResponse::decode(&response).unwrap().description, "GATEWAY\u{fffd}CTRL");
+        assert!(matches!(VersionResponse::decode_strict(&response), Err(ParseError::UTF8(_))));
+    }
+
+    // Common command encoding
+    // -------------------------------------------------------------------
+    #[test]
+    fn given_reset_command_then_encode_co_wr_reset_frame() {
+        let frame = Packet::CommonCommand(CommonCommand::Reset).encode();
+        let bytes: &[u8] = std::borrow::Borrow::borrow(&frame);
+        assert_eq!(bytes, &[0x55, 0x00, 0x01, 0x00, 0x05, 0x70, 0x02, 0x0e]);
+    }
+
+    #[test]
+    fn given_read_id_base_command_then_encode_co_rd_idbase_frame() {
+        let frame = Packet::CommonCommand(CommonCommand::ReadIdBase).encode();
+        assert_eq!(frame.packet_type(), 0x05);
+        assert_eq!(frame.data(), &[0x08]);
+        assert!(frame.optional_data().is_empty());
+    }
+
+    #[test]
+    fn given_write_id_base_command_then_encode_co_wr_idbase_frame() {
+        let base = Address([0xff, 0x9b, 0x12, 0x80]);
+        let frame = Packet::CommonCommand(CommonCommand::WriteIdBase { base }).encode();
+        assert_eq!(frame.packet_type(), 0x05);
+        assert_eq!(frame.data(), &[0x07, 0xff, 0x9b, 0x12, 0x80]);
+        assert!(frame.optional_data().is_empty());
+    }
+
+    #[test]
+    fn given_smart_ack_learn_mode_command_then_encode_sa_wr_learnmode_frame() {
+        let command = SmartAck::WriteLearnMode { enable: true, extended: 0, timeout: 60_000 };
+        let frame = Packet::SmartAck(command).encode();
+        assert_eq!(frame.packet_type(), 0x06);
+        assert_eq!(frame.data(), &[0x01, 0x01, 0x00, 0x00, 0x00, 0xea, 0x60]);
+        assert!(frame.optional_data().is_empty());
+
+        match Packet::decode(frame.as_ref()).unwrap() {
+            Packet::SmartAck(decoded) => assert_eq!(decoded, command),
+            packet => panic!("Expected a Smart Ack command, got {:?}", packet),
+        }
+    }
+
+    #[test]
+    fn given_smart_ack_commands_then_encode_and_decode_them_back() {
+        let client = Address([0x01, 0x82, 0x5d, 0xab]);
+        for (command, data) in [
+            (SmartAck::ReadLearnedClients, vec![0x06]),
+            (SmartAck::Reset { client }, vec![0x05, 0x01, 0x82, 0x5d, 0xab]),
+        ] {
+            let frame = Packet::SmartAck(command).encode();
+            assert_eq!(frame.data(), &data[..]);
+            assert_eq!(SmartAck::decode(frame.as_ref()).unwrap(), command);
+        }
+        let truncated = ESP3Frame::assemble(0x06, &[0x01, 0x01, 0x00], &[]);
+        assert!(matches!(SmartAck::decode(truncated.as_ref()), Err(ParseError::PacketTooShort)));
+    }
+
+    #[test]
+    fn given_sa_rd_learnedclients_response_then_decode_clients() {
+        let response = Response {
+            code: ResponseCode::Ok,
+            data: vec![0x01, 0x82, 0x5d, 0xab, 0xff, 0x9b, 0x12, 0x80, 0x00, 0x01, 0x82, 0x5d, 0xac, 0xff, 0x9b, 0x12, 0x80, 0x01],
+            optional: vec![],
+        };
+        let clients = LearnedClient::decode_all(&response).unwrap();
+        assert_eq!(clients.len(), 2);
+        assert_eq!(clients[1], LearnedClient {
+            client: Address([0x01, 0x82, 0x5d, 0xac]),
+            controller: Address([0xff, 0x9b, 0x12, 0x80]),
+            mailbox_index: 1,
+        });
+    }
+
+    #[test]
+    fn given_address_then_format_and_parse_it_back() {
+        let address = Address::from([0x05, 0x0a, 0x3d, 0x6a]);
+        assert_eq!(address.to_string(), "05:0A:3D:6A");
+        assert_eq!("05:0A:3D:6A".parse::<Address>().unwrap(), address);
+        assert_eq!("05:0a:3d:6a".parse::<Address>().unwrap().as_bytes(), &[0x05, 0x0a, 0x3d, 0x6a]);
+
+        for invalid in ["", "05:0A:3D", "05:0A:3D:6A:00", "5:0A:3D:6A", "05:0A:3D:6G", "050A3D6A", "+5:0A:3D:6A"] {
+            assert!(invalid.parse::<Address>().is_err());
+        }
+    }
+
+    #[test]
+    fn given_status_bytes_then_decode_rps_flags_and_repeater_count() {
+        // F6-02-01 pressed, then released
+        let pressed = Status(0x30);
+        assert!(pressed.t21() && pressed.is_nu());
+        let released = Status(0x20);
+        assert!(released.t21() && !released.is_nu());
+        assert_eq!(released.repeater_count(), 0);
+
+        // Repeated once
+        let repeated = Status::from(0x21);
+        assert!(repeated.is_repeated());
+        assert_eq!(repeated.repeater_count(), 1);
+        assert_eq!(u8::from(repeated), 0x21);
+    }
+
+    #[test]
+    fn given_co_rd_idbase_response_then_decode_base_id() {
+        // Common command : read Base_ID of TCM300. BASE ID = 255, 155, 18, 128, 10 remaining writes
+        let received_message = [85, 0, 5, 1, 2, 219, 0, 255, 155, 18, 128, 10, 17];
+        let frame = ESP3Frame::read_from(&mut &received_message[..]).unwrap();
+        let response = Response::decode(frame.as_ref()).unwrap();
+        let base_id = BaseIdResponse::decode(&response).unwrap();
+        assert_eq!(base_id.base_id, Address([255, 155, 18, 128]));
+        assert_eq!(base_id.remaining_writes, Some(10));
+
+        let response = Response { code: ResponseCode::Ok, data: vec![255, 155, 18], optional: vec![] };
+        assert!(matches!(BaseIdResponse::decode(&response), Err(ParseError::PacketTooShort)));
+    }
+
+    // Event decoding
+    // -------------------------------------------------------------------
+    #[test]
+    fn given_co_ready_frame_then_decode_event_with_optional_mode() {
+        // CO_READY, wakeup cause = 0x00 (voltage supply drop), mode = 0x00 (standard security)
+        let frame = ESP3Frame::assemble(0x04, &[0x04, 0x00], &[0x00]);
+        match Packet::decode(frame.as_ref()).unwrap() {
+            Packet::Event(Event::COReady { wakeup, mode }) => {
+                assert_eq!(wakeup, 0x00);
+                assert_eq!(mode, Some(0x00));
+            }
+            p => panic!("Expected a COReady event, got {:?}", p),
+        }
+
+        let frame = ESP3Frame::assemble(0x04, &[0x04, 0x02], &[]);
+        match Event::decode(frame.as_ref()).unwrap() {
+            Event::COReady { wakeup, mode } => {
+                assert_eq!(wakeup, 0x02);
+                assert_eq!(mode, None);
+            }
+            e => panic!("Expected a COReady event, got {:?}", e),
+        }
+    }
+
+    #[test]
+    fn given_co_duty_cycle_limit_frame_then_decode_event() {
+        let frame = ESP3Frame::assemble(0x04, &[0x06, 0x01], &[]);
+        match Event::decode(frame.as_ref()).unwrap() {
+            Event::CODutyCycleLimit { cause } => assert_eq!(cause, 0x01),
+            e => panic!("Expected a CODutyCycleLimit event, got {:?}", e),
+        }
+        assert_eq!(Event::decode(frame.as_ref()).unwrap().duty_cycle_limit_reached(), Some(true));
+        let released = ESP3Frame::assemble(0x04, &[0x06, 0x00], &[]);
+        assert_eq!(Event::decode(released.as_ref()).unwrap().duty_cycle_limit_reached(), Some(false));
+        assert_eq!(Event::COTXDone.duty_cycle_limit_reached(), None);
+    }
+
+    #[test]
+    fn given_duty_cycle_response_then_decode_it() {
+        let frame = Packet::CommonCommand(CommonCommand::ReadDutyCycleLimit).encode();
+        assert_eq!(frame.data(), &[0x23]);
+
+        // 80 % available, 10 slots of 360 s, 120 s left, 100 % in the next slot
+        let response = Response::decode(ESP3Frame::assemble(0x02, &[0x00, 80, 10, 0x01, 0x68, 0x00, 0x78, 100], &[]).as_ref()).unwrap();
+        assert_eq!(
+            DutyCycleResponse::decode(&response).unwrap(),
+            DutyCycleResponse { available: 80, slots: 10, slot_period: 360, slot_left: 120, load_after_actual: 100 }
+        );
+        let response = Response { code: ResponseCode::Ok, data: vec![80, 10], optional: vec![] };
+        assert!(matches!(DutyCycleResponse::decode(&response), Err(ParseError::PacketTooShort)));
+    }
+
+    #[test]
+    fn given_truncated_or_unknown_event_then_return_error_or_unknown() {
+        let frame = ESP3Frame::assemble(0x04, &[0x06], &[]);
+        assert!(matches!(Event::decode(frame.as_ref()), Err(ParseError::PacketTooShort)));
+
+        let frame = ESP3Frame::assemble(0x04, &[0x42, 0x01], &[]);
+        assert!(matches!(Event::decode(frame.as_ref()), Ok(Event::Unknown { code: 0x42, .. })));
+    }
+
+    #[test]
+    fn given_event_frames_then_encode_decoded_events_back() {
+        let mut confirm_learn = vec![0x02];
+        confirm_learn.extend(0..16);
+        for (data, optional) in [
+            (vec![0x01], vec![]),
+            (confirm_learn, vec![]),
+            (vec![0x03, 0x00, 0x01, 0x02], vec![]),
+            (vec![0x04, 0x02], vec![0x01]),
+            (vec![0x05, 0x01, 0x01, 0x82, 0x5d, 0xab], vec![]),
+            (vec![0x06, 0x01], vec![]),
+            (vec![0x07, 0x11], vec![]),
+            (vec![0x08], vec![]),
+            (vec![0x09], vec![]),
+            (vec![0x42, 0x01], vec![0x02]),
+        ] {
+            let frame = ESP3Frame::assemble(0x04, &data, &optional);
+            let encoded = Packet::decode(frame.as_ref()).unwrap().encode();
+            assert_eq!(Borrow::<[u8]>::borrow(&encoded), Borrow::<[u8]>::borrow(&frame));
+        }
+    }
+}
@@ -1,37 +1,237 @@
 //! Stateful link to an ESP3 device
 
-use serialport::{self, SerialPort};
+use serialport::{self, SerialPort, SerialPortInfo, SerialPortType};
 use std::collections::VecDeque;
+use std::io::{Read, Write};
+use std::time::{Duration, Instant};
+use log::{debug, trace, warn};
 
-use crate::{frame::{ESP3Frame, ESP3FrameRef}, FrameReadError, packet::{Packet, CommonCommand, Response, VersionResponse}, PacketError};
+use crate::{frame::{ESP3Frame, ESP3FrameRef}, FrameReadError, packet::{Address, BaseIdResponse, Packet, ParseError, CommonCommand, DutyCycleResponse, RemoteManagement, RepeaterResponse, Response, ResponseCode, SmartAck, VersionResponse}, PacketError};
+
+/// USB (vendor id, product id) of known EnOcean gateways. The USB300 is FTDI-based.
+const KNOWN_GATEWAYS: &[(u16, u16)] = &[(0x0403, 0x6001)];
+
+/// Whether a serial port looks like an EnOcean gateway, based on its USB ids or description.
+fn is_enocean_gateway(info: &SerialPortInfo) -> bool {
+    GatewayInfo::from(info).is_likely_enocean()
+}
+
+/// Description of an available serial port, see [`list_gateways`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GatewayInfo {
+    pub port_name: String,
+    /// USB vendor id, for USB ports
+    pub vid: Option<u16>,
+    /// USB product id, for USB ports
+    pub pid: Option<u16>,
+    pub manufacturer: Option<String>,
+    pub product: Option<String>,
+}
+
+impl GatewayInfo {
+    /// Whether the port looks like an EnOcean gateway (eg. an USB300), based on its USB ids or description.
+    pub fn is_likely_enocean(&self) -> bool {
+        let mentions_enocean = |s: &Option<String>| s.as_deref().is_some_and(|s| s.contains("EnOcean"));
+        let known_ids = match (self.vid, self.pid) {
+            (Some(vid), Some(pid)) => KNOWN_GATEWAYS.contains(&(vid, pid)),
+            _ => false,
+        };
+        known_ids || mentions_enocean(&self.manufacturer) || mentions_enocean(&self.product)
+    }
+}
+
+impl From<&SerialPortInfo> for GatewayInfo {
+    fn from(info: &SerialPortInfo) -> Self {
+        let usb = match &info.port_type {
+            SerialPortType::UsbPort(usb) => Some(usb),
+            _ => None,
+        };
+        GatewayInfo {
+            port_name: info.port_name.clone(),
+            vid: usb.map(|usb| usb.vid),
+            pid: usb.map(|usb| usb.pid),
+            manufacturer: usb.and_then(|usb| usb.manufacturer.clone()),
+            product: usb.and_then(|usb| usb.product.clone()),
+        }
+    }
+}
+
+/// Describe every available serial port, likely EnOcean gateways first.
+/// Returns an empty list if the ports can not be listed.
+pub fn list_gateways() -> Vec<GatewayInfo> {
+    match serialport::available_ports() {
+        Ok(ports) => gateways_of(&ports),
+        Err(e) => {
+            warn!("Error listing serial ports ({:?}) : {}", e.kind(), e);
+            vec![]
+        }
+    }
+}
+
+/// The first available serial port which looks like an EnOcean gateway, if any.
+pub fn find_gateway() -> Option<GatewayInfo> {
+    first_gateway(list_gateways())
+}
+
+fn first_gateway(gateways: Vec<GatewayInfo>) -> Option<GatewayInfo> {
+    gateways.into_iter().find(GatewayInfo::is_likely_enocean)
+}
+
+fn gateways_of(ports: &[SerialPortInfo]) -> Vec<GatewayInfo> {
+    let mut gateways: Vec<GatewayInfo> = ports.iter().map(GatewayInfo::from).collect();
+    // Stable sort : keep the system order otherwise
+    gateways.sort_by_key(|gateway| !gateway.is_likely_enocean());
+    gateways
+}
+
+/// Util : `duration` in ms, capped to `u32::MAX` ms as in learn mode commands
+fn millis_u32(duration: Duration) -> u32 {
+    duration.as_millis().min(u32::MAX as u128) as u32
+}
+
+/// How many unrelated frames we accept to receive while waiting for a response.
+const MAX_FRAMES_BEFORE_RESPONSE: usize = 32;
+
+/// Read frames until a response (packet type 0x02) arrives. Other frames are pushed to `queue`.
+fn read_response(reader: &mut impl Read, queue: &mut VecDeque<ESP3Frame>) -> Result<ESP3Frame, PacketError> {
+    read_response_until(reader, queue, None)
+}
+
+/// Same as `read_response`, but gives up with `FrameReadError::Timeout` once `deadline` is reached.
+fn read_response_until(reader: &mut impl Read, queue: &mut VecDeque<ESP3Frame>, deadline: Option<Instant>) -> Result<ESP3Frame, PacketError> {
+    for _ in 0..MAX_FRAMES_BEFORE_RESPONSE {
+        let frame = match deadline {
+            Some(deadline) => ESP3Frame::read_from_timeout(reader, deadline.saturating_duration_since(Instant::now()))?,
+            None => ESP3Frame::read_from(reader)?,
+        };
+        if frame.packet_type() == 0x02 {
+            return Ok(frame);
+        }
+        trace!("Queueing frame of type {:#04x} while waiting for a response", frame.packet_type());
+        queue.push_back(frame);
+    }
+    Err(PacketError::NoResponse)
+}
+
+/// How `Port::write_packet_with_retry` resends a packet which was not answered, or answered with `NotSupported`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// How many times the packet is sent, at most
+    pub attempts: u32,
+    /// Delay before resending, doubled after each failed attempt
+    pub backoff: Duration,
+    /// How long we wait for each response
+    pub timeout: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy { attempts: 3, backoff: Duration::from_millis(100), timeout: Duration::from_secs(1) }
+    }
+}
+
+/// Serial settings used by `Port::open_with_config`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortConfig {
+    /// 57600 bauds for ESP3
+    pub baud_rate: u32,
+    /// Read timeout of the port, if any
+    pub timeout: Option<Duration>,
+}
+
+impl Default for PortConfig {
+    fn default() -> Self {
+        PortConfig { baud_rate: 57600, timeout: None }
+    }
+}
+
+/// A frame received by `Port::events`, to decode into a `Packet`.
+///
+/// A `Packet` borrows its frame, so the frame is kept here and the packet decoded from it on demand.
+#[derive(Clone, Debug)]
+pub struct ReceivedPacket(ESP3Frame);
+
+impl ReceivedPacket {
+    /// Decode the packet, or return why the frame is not a supported packet
+    pub fn packet(&self) -> Result<Packet<'_>, ParseError> {
+        Packet::decode(self.0.as_ref())
+    }
+
+    /// The received frame
+    pub fn frame(&self) -> &ESP3Frame {
+        &self.0
+    }
+}
 
 /// An opened ESP3 device.
-pub struct Port {
-    port: Box<dyn SerialPort>,
+///
+/// Usually a serial port, but any byte stream which can be read and written is accepted (eg. a [`MockPort`](crate::mock::MockPort)).
+///
+/// Writes may be buffered by the underlying stream, and dropping a `Port` does not flush it :
+/// use `flush` or `close` to make sure a last command reaches the device.
+pub struct Port<P = Box<dyn SerialPort>> {
+    port: P,
 
-    /// In the future, this should store pending requests so that we can route the responses to the correct sender.
+    /// Name and settings of the opened serial port, `None` for a stream given to `Port::new`
+    port_name: Option<String>,
+    config: Option<PortConfig>,
+
+    /// Frames received while waiting for a response, see `poll_event`.
     queue: VecDeque<ESP3Frame>
 }
 
 impl Port {
 
+    /// Open the first available serial port which looks like an EnOcean gateway (eg. an USB300).
     pub fn open_default() -> Result<Self, serialport::Error> {
-        todo!()
+        Self::open_default_matching(is_enocean_gateway)
+    }
+
+    /// Open the first available serial port accepted by `predicate`.
+    pub fn open_default_matching(predicate: impl Fn(&SerialPortInfo) -> bool) -> Result<Self, serialport::Error> {
+        let ports = serialport::available_ports()?;
+        let info = ports.iter()
+            .find(|info| predicate(info))
+            .ok_or_else(|| serialport::Error::new(serialport::ErrorKind::NoDevice, "No EnOcean gateway detected"))?;
+        Self::open(&info.port_name)
     }
 
     pub fn open(port_name: &str) -> Result<Self, serialport::Error> {
-        let baud_rate = 57600;
-        let port = serialport::new(port_name, baud_rate)
-            //.timeout(Duration::from_millis(100))
+        Self::open_with_config(port_name, PortConfig::default())
+    }
+
+    /// Open the serial port `port_name` with the given baud rate and read timeout.
+    pub fn open_with_config(port_name: &str, config: PortConfig) -> Result<Self, serialport::Error> {
+        let mut builder = serialport::new(port_name, config.baud_rate)
             .data_bits(serialport::DataBits::Eight)
             .parity(serialport::Parity::None)
             .stop_bits(serialport::StopBits::One)
-            .flow_control(serialport::FlowControl::None)
-            .open()?;
+            .flow_control(serialport::FlowControl::None);
+        if let Some(timeout) = config.timeout {
+            builder = builder.timeout(timeout);
+        }
+        let port = builder.open()?;
+
+        debug!("Opened ESP3 device on {}", port_name);
+        Ok(Port { port, port_name: Some(String::from(port_name)), config: Some(config), queue: VecDeque::new() })
+    }
+}
+
+impl<P: Read + Write> Port<P> {
+
+    /// Use an already opened byte stream as an ESP3 device.
+    pub fn new(port: P) -> Self {
+        Port { port, port_name: None, config: None, queue: VecDeque::new() }
+    }
 
-        let queue = VecDeque::new();
+    /// Name of the serial port (eg. `/dev/ttyUSB0`), `None` if the port was not opened by `Port::open`
+    pub fn port_name(&self) -> Option<&str> {
+        self.port_name.as_deref()
+    }
 
-        Ok(Self { port, queue })
+    /// Serial settings the port was opened with, `None` if the port was not opened by `Port::open`
+    pub fn config(&self) -> Option<&PortConfig> {
+        self.config.as_ref()
     }
 
     pub fn read_version_information(&mut self) -> Result<VersionResponse, PacketError> {
@@ -39,11 +239,117 @@ impl Port {
         Ok(VersionResponse::decode(&response)?)
     }
 
+    /// Read the base ID of the gateway (CO_RD_IDBASE)
+    pub fn read_base_id(&mut self) -> Result<Address, PacketError> {
+        let response = self.write_packet(Packet::CommonCommand(CommonCommand::ReadIdBase))?;
+        Ok(BaseIdResponse::decode(&response)?.base_id)
+    }
+
+    /// Write the base ID of the gateway (CO_WR_IDBASE), and return its response code.
+    ///
+    /// The base ID can only be changed a limited number of times:
+    /// `PacketError::WriteDenied` is returned when the gateway refuses to change it.
+    pub fn write_base_id(&mut self, base: Address) -> Result<ResponseCode, PacketError> {
+        let response = self.write_packet(Packet::CommonCommand(CommonCommand::WriteIdBase { base }))?;
+        match response.code {
+            code @ (ResponseCode::OperationDenied | ResponseCode::LockSet) => Err(PacketError::WriteDenied(code)),
+            code => Ok(code),
+        }
+    }
+
+    /// Enable the repeater of the gateway at `level` 1 or 2, or disable it (CO_WR_REPEATER), and return its response code.
+    pub fn set_repeater(&mut self, enabled: bool, level: u8) -> Result<ResponseCode, PacketError> {
+        if enabled && !(1..=2).contains(&level) {
+            return Err(PacketError::InvalidParameter("Repeater level must be 1 or 2"));
+        }
+        let response = self.write_packet(Packet::CommonCommand(CommonCommand::WriteRepeater { enabled, level }))?;
+        Ok(response.code)
+    }
+
+    /// Read the repeater settings of the gateway (CO_RD_REPEATER)
+    pub fn read_repeater(&mut self) -> Result<RepeaterResponse, PacketError> {
+        let response = self.write_packet(Packet::CommonCommand(CommonCommand::ReadRepeater))?;
+        Ok(RepeaterResponse::decode(&response)?)
+    }
+
+    /// Read the duty cycle status of a sub-1GHz gateway (CO_GET_DUTYCYCLE_LIMIT), eg. to back off transmissions.
+    ///
+    /// Gateways which do not enforce a duty cycle answer `NotSupported` without any data, reported as a `PacketTooShort` error.
+    pub fn read_duty_cycle_limit(&mut self) -> Result<DutyCycleResponse, PacketError> {
+        let response = self.write_packet(Packet::CommonCommand(CommonCommand::ReadDutyCycleLimit))?;
+        Ok(DutyCycleResponse::decode(&response)?)
+    }
+
+    /// Reset the gateway (CO_WR_RESET), and return its response code.
+    ///
+    /// The gateway reboots after answering: expect a `COReady` event afterward.
+    pub fn reset(&mut self) -> Result<ResponseCode, PacketError> {
+        let response = self.write_packet(Packet::CommonCommand(CommonCommand::Reset))?;
+        Ok(response.code)
+    }
+
+    /// Enable or disable the Smart Ack learn mode of the gateway (SA_WR_LEARNMODE), in simple learn mode, and return its response code.
+    ///
+    /// The learn mode is left after `timeout`, which is capped to `u32::MAX` ms.
+    pub fn set_smart_ack_learn_mode(&mut self, enabled: bool, timeout: Duration) -> Result<ResponseCode, PacketError> {
+        let timeout = millis_u32(timeout);
+        let response = self.write_packet(Packet::SmartAck(SmartAck::WriteLearnMode { enable: enabled, extended: 0, timeout }))?;
+        Ok(response.code)
+    }
+
+    /// Send a remote management command to a remote device, see `RemoteManagement::query_id` and `RemoteManagement::action`.
+    ///
+    /// The response only acknowledges the sending; answers of the remote device are received as events.
+    pub fn send_remote_management(&mut self, command: RemoteManagement) -> Result<ResponseCode, PacketError> {
+        let response = self.write_packet(Packet::RemoteManagement(command))?;
+        Ok(response.code)
+    }
+
+    /// Enable or disable the learn mode of the controller (CO_WR_LEARNMODE), eg. to teach-in devices.
+    /// The learn mode is left after `timeout` (capped to `u32::MAX` ms), or after the default timeout of the gateway if `None`.
+    pub fn set_learn_mode(&mut self, enabled: bool, timeout: Option<Duration>) -> Result<ResponseCode, PacketError> {
+        let timeout = timeout.map(millis_u32);
+        let response = self.write_packet(Packet::CommonCommand(CommonCommand::WriteLearnMode { enabled, timeout }))?;
+        Ok(response.code)
+    }
+
     /// Read the next frame from the port.
     pub fn read_frame(&mut self) -> Result<ESP3Frame, FrameReadError> {
         ESP3Frame::read_from(&mut self.port)
     }
 
+    /// Get the next received frame which is not a response to one of our requests.
+    ///
+    /// Frames received while `write_packet` was waiting for a response are returned first.
+    pub fn poll_event(&mut self) -> Option<ESP3Frame> {
+        self.queue.pop_front().or_else(|| self.read_frame().ok())
+    }
+
+    /// Iterate over the received packets, starting with the frames queued while waiting for a response.
+    ///
+    /// Like `ESP3Frame::iter_from`, the iterator ends at the end of the stream, or after yielding an IO error (except read timeouts).
+    /// Frames which cannot be read are yielded as errors, and reading goes on with the next frame.
+    /// Each packet is decoded by `ReceivedPacket::packet`.
+    pub fn events(&mut self) -> impl Iterator<Item = Result<ReceivedPacket, PacketError>> + '_ {
+        self.queue
+            .drain(..)
+            .map(Ok)
+            .chain(ESP3Frame::iter_from(&mut self.port))
+            .map(|frame| Ok(ReceivedPacket(frame?)))
+    }
+
+    /// Flush the pending writes to the device.
+    pub fn flush(&mut self) -> Result<(), std::io::Error> {
+        self.port.flush()
+    }
+
+    /// Flush the pending writes, then close the port.
+    pub fn close(mut self) -> Result<(), std::io::Error> {
+        self.flush()?;
+        debug!("Closed ESP3 device");
+        Ok(())
+    }
+
     /// Write a frame to the port.
     pub fn write_frame(&mut self, frame: &ESP3Frame) -> Result<(), std::io::Error> {
         frame.write_to(&mut self.port)
@@ -61,17 +367,326 @@ impl Port {
         let frame = packet.encode();
         self.write_frame(&frame)?;
 
-        let reply = loop {
-            let frame = self.read_frame()?;
-            if frame.packet_type() != 0x02 {
-                self.queue.push_back(frame);
-            } else {
-                break frame;
+        let reply = read_response(&mut self.port, &mut self.queue)?;
+        Ok(Response::decode(reply.as_ref())?)
+
+    }
+
+    /// Write a frame (eg. a radio telegram) and return the response code of the gateway,
+    /// which tells whether it was transmitted.
+    ///
+    /// Radio telegrams and events received before the response are queued, see `poll_event`.
+    pub fn transmit(&mut self, frame: &ESP3Frame) -> Result<ResponseCode, PacketError> {
+        self.write_frame(frame)?;
+        let reply = read_response(&mut self.port, &mut self.queue)?;
+        Ok(Response::decode(reply.as_ref())?.code)
+    }
+
+    /// Write a packet and wait up to `timeout` for its response.
+    ///
+    /// Radio telegrams and events received in the meantime are queued, see `poll_event`.
+    /// `PacketError::FrameError(FrameReadError::Timeout)` is returned if no response arrived in time.
+    /// The port must have a read timeout, otherwise a read may block past `timeout`.
+    pub fn send_and_await_response(&mut self, packet: Packet, timeout: Duration) -> Result<Response, PacketError> {
+        self.exchange_frame(&packet.encode(), timeout)
+    }
+
+    /// Write a packet and wait for its response, resending it according to `policy`
+    /// when no response arrived in time, or when the gateway answered `NotSupported`.
+    ///
+    /// Returns the last response, or the last error once every attempt failed.
+    pub fn write_packet_with_retry(&mut self, packet: Packet, policy: RetryPolicy) -> Result<Response, PacketError> {
+        let frame = packet.encode();
+        let mut backoff = policy.backoff;
+        let mut attempt = 1;
+        loop {
+            let result = self.exchange_frame(&frame, policy.timeout);
+            let retryable = match &result {
+                Ok(response) => response.code == ResponseCode::NotSupported,
+                Err(PacketError::FrameError(FrameReadError::Timeout) | PacketError::NoResponse) => true,
+                Err(_) => false,
+            };
+            if !retryable || attempt >= policy.attempts {
+                return result;
             }
-        };
+            debug!("No valid response to attempt {}/{}, resending in {:?}", attempt, policy.attempts, backoff);
+            std::thread::sleep(backoff);
+            backoff *= 2;
+            attempt += 1;
+        }
+    }
 
+    /// Write a frame and wait up to `timeout` for its response.
+    fn exchange_frame(&mut self, frame: &ESP3Frame, timeout: Duration) -> Result<Response, PacketError> {
+        let deadline = Instant::now() + timeout;
+        self.write_frame(frame)?;
+
+        let reply = read_response_until(&mut self.port, &mut self.queue, Some(deadline))?;
         Ok(Response::decode(reply.as_ref())?)
+    }
+
+}
+
+/// Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serialport::UsbPortInfo;
+    use crate::mock::MockPort;
+
+    #[test]
+    fn given_erp1_frames_before_response_then_queue_them_and_return_response() {
+        let mut stream = vec![];
+        let erp1 = ESP3Frame::assemble(0x01, &[246, 112, 254, 245, 143, 245, 48], &[1, 255, 255, 255, 255, 46, 0]);
+        let event = ESP3Frame::assemble(0x04, &[0x04, 0x00], &[]);
+        let response = ESP3Frame::assemble(0x02, &[0x00], &[]);
+        erp1.write_to(&mut stream).unwrap();
+        event.write_to(&mut stream).unwrap();
+        response.write_to(&mut stream).unwrap();
+        erp1.write_to(&mut stream).unwrap();
+
+        let mut queue = VecDeque::new();
+        let mut reader = &stream[..];
+        let reply = read_response(&mut reader, &mut queue).unwrap();
+
+        assert_eq!(reply.packet_type(), 0x02);
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue[0].data(), erp1.data());
+        assert_eq!(queue[1].packet_type(), 0x04);
+        // The frame following the response is left unread
+        assert_eq!(ESP3Frame::read_from(&mut reader).unwrap().data(), erp1.data());
+    }
+
+    #[test]
+    fn given_no_response_then_stop_after_max_frames() {
+        let mut stream = vec![];
+        let erp1 = ESP3Frame::assemble(0x01, &[246, 112, 254, 245, 143, 245, 48], &[]);
+        for _ in 0..MAX_FRAMES_BEFORE_RESPONSE + 1 {
+            erp1.write_to(&mut stream).unwrap();
+        }
+
+        let mut queue = VecDeque::new();
+        let result = read_response(&mut &stream[..], &mut queue);
+        assert!(matches!(result, Err(PacketError::NoResponse)));
+        assert_eq!(queue.len(), MAX_FRAMES_BEFORE_RESPONSE);
+    }
+
+    #[test]
+    fn given_mock_port_then_write_reset_command_and_read_its_response() {
+        let mut response = vec![];
+        ESP3Frame::assemble(0x02, &[0x00], &[]).write_to(&mut response).unwrap();
+        let mut port = Port::new(MockPort::new([response]));
+
+        assert!(matches!(port.reset(), Ok(ResponseCode::Ok)));
+
+        let mut command = vec![];
+        Packet::CommonCommand(CommonCommand::Reset).encode().write_to(&mut command).unwrap();
+        assert_eq!(port.port.written(), &command[..]);
+    }
+
+    #[test]
+    fn given_erp1_before_response_then_send_and_await_response_returns_it_and_queues_erp1() {
+        let erp1 = ESP3Frame::assemble(0x01, &[246, 112, 254, 245, 143, 245, 48], &[1, 255, 255, 255, 255, 46, 0]);
+        let mut erp1_bytes = vec![];
+        erp1.write_to(&mut erp1_bytes).unwrap();
+        let mut response = vec![];
+        ESP3Frame::assemble(0x02, &[0x00, 0xff, 0x9b, 0x12, 0x80], &[0x0a]).write_to(&mut response).unwrap();
+        let mut port = Port::new(MockPort::new([erp1_bytes, response]));
+
+        let reply = port
+            .send_and_await_response(Packet::CommonCommand(CommonCommand::ReadIdBase), Duration::from_secs(1))
+            .unwrap();
+        assert_eq!(reply.code, ResponseCode::Ok);
+        assert_eq!(reply.data, vec![0xff, 0x9b, 0x12, 0x80]);
+
+        let mut command = vec![];
+        Packet::CommonCommand(CommonCommand::ReadIdBase).encode().write_to(&mut command).unwrap();
+        assert_eq!(port.port.written(), &command[..]);
+        assert_eq!(port.poll_event().unwrap().data(), erp1.data());
+    }
+
+    #[test]
+    fn given_queued_and_received_erp1_frames_then_iterate_over_their_packets() {
+        let queued = ESP3Frame::assemble(0x04, &[0x04, 0x00], &[]);
+        let first = ESP3Frame::assemble(0x01, &[246, 112, 254, 245, 143, 245, 48], &[1, 255, 255, 255, 255, 46, 0]);
+        let second = ESP3Frame::assemble(0x01, &[213, 8, 1, 133, 106, 33, 0], &[1, 255, 255, 255, 255, 52, 0]);
+        let mut stream = vec![];
+        first.write_to(&mut stream).unwrap();
+        second.write_to(&mut stream).unwrap();
+        let mut port = Port::new(MockPort::new([stream]));
+        port.queue.push_back(queued);
+
+        let events: Vec<_> = port.events().collect();
+        assert_eq!(events.len(), 4);
+        assert!(matches!(events[0].as_ref().unwrap().packet(), Ok(Packet::Event(_))));
+        for (event, sent) in events[1..3].iter().zip([&first, &second]) {
+            match event.as_ref().unwrap().packet().unwrap() {
+                Packet::RadioErp1(erp1) => assert_eq!(erp1.sender_id, Address::from(<[u8; 4]>::try_from(&sent.data()[2..6]).unwrap())),
+                other => panic!("Unexpected packet {:?}", other),
+            }
+        }
+        // The mock port is exhausted
+        assert!(matches!(events[3], Err(PacketError::FrameError(FrameReadError::IOError(_)))));
+    }
+
+    #[test]
+    fn given_unsupported_packet_type_then_return_error_when_decoding_it() {
+        let mut stream = vec![];
+        ESP3Frame::assemble(0x42, &[0x01], &[]).write_to(&mut stream).unwrap();
+        let mut port = Port::new(MockPort::new([stream]));
+
+        let event = port.events().next().unwrap().unwrap();
+        assert_eq!(event.frame().packet_type(), 0x42);
+        assert!(matches!(event.packet(), Err(ParseError::UnsupportedPacketType)));
+    }
+
+    #[test]
+    fn given_two_not_supported_responses_then_retry_until_ok() {
+        let response = |code: u8| {
+            let mut bytes = vec![];
+            ESP3Frame::assemble(0x02, &[code], &[]).write_to(&mut bytes).unwrap();
+            bytes
+        };
+        let policy = RetryPolicy { attempts: 3, backoff: Duration::from_millis(1), timeout: Duration::from_secs(1) };
+
+        let mut port = Port::new(MockPort::new([response(0x02), response(0x02), response(0x00)]));
+        let reply = port.write_packet_with_retry(Packet::CommonCommand(CommonCommand::Reset), policy).unwrap();
+        assert_eq!(reply.code, ResponseCode::Ok);
+        let mut command = vec![];
+        Packet::CommonCommand(CommonCommand::Reset).encode().write_to(&mut command).unwrap();
+        assert_eq!(port.port.written(), &command.repeat(3)[..]);
+
+        // Give up after the last attempt
+        let mut port = Port::new(MockPort::new([response(0x02), response(0x02)]));
+        let policy = RetryPolicy { attempts: 2, ..policy };
+        let reply = port.write_packet_with_retry(Packet::CommonCommand(CommonCommand::Reset), policy).unwrap();
+        assert_eq!(reply.code, ResponseCode::NotSupported);
+    }
+
+    #[test]
+    fn given_repeater_settings_then_validate_write_and_read_them() {
+        let mut port = Port::new(MockPort::default());
+        assert!(matches!(port.set_repeater(true, 3), Err(PacketError::InvalidParameter(_))));
+        assert!(port.port.written().is_empty());
+
+        let mut responses = vec![];
+        ESP3Frame::assemble(0x02, &[0x00], &[]).write_to(&mut responses).unwrap();
+        ESP3Frame::assemble(0x02, &[0x00, 0x01, 0x02], &[]).write_to(&mut responses).unwrap();
+        let mut port = Port::new(MockPort::new([responses]));
+        assert!(matches!(port.set_repeater(true, 2), Ok(ResponseCode::Ok)));
+        assert_eq!(port.read_repeater().unwrap(), RepeaterResponse { enabled: true, level: 2 });
+    }
+
+    #[test]
+    fn given_telegram_to_transmit_then_return_response_code_and_queue_other_frames() {
+        let received = ESP3Frame::assemble(0x01, &[246, 112, 254, 245, 143, 245, 48], &[1, 255, 255, 255, 255, 46, 0]);
+        let mut stream = vec![];
+        received.write_to(&mut stream).unwrap();
+        ESP3Frame::assemble(0x02, &[0x00], &[]).write_to(&mut stream).unwrap();
+        let mut port = Port::new(MockPort::new([stream]));
+
+        let telegram = ESP3Frame::assemble(0x01, &[246, 0x10, 0, 0, 0, 0, 0x30], &[3, 255, 255, 255, 255, 255, 0]);
+        assert!(matches!(port.transmit(&telegram), Ok(ResponseCode::Ok)));
+        assert_eq!(port.port.written(), telegram.as_bytes());
+        assert_eq!(port.poll_event().unwrap().data(), received.data());
+    }
 
+    #[test]
+    fn given_stream_or_missing_port_then_have_no_name_nor_config() {
+        let config = PortConfig { baud_rate: 57600, timeout: Some(Duration::from_millis(100)) };
+        assert!(Port::open_with_config("/dev/does-not-exist", config.clone()).is_err());
+
+        let port = Port::new(MockPort::default());
+        assert_eq!(port.port_name(), None);
+        assert_eq!(port.config(), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn given_opened_serial_port_then_report_its_name_and_config() {
+        // A pseudo terminal stands for the gateway
+        let (_gateway, tty) = serialport::TTYPort::pair().unwrap();
+        let tty_name = tty.name().unwrap();
+
+        let config = PortConfig { baud_rate: 57600, timeout: Some(Duration::from_millis(100)) };
+        let port = Port::open_with_config(&tty_name, config.clone()).unwrap();
+        assert_eq!(port.port_name(), Some(tty_name.as_str()));
+        assert_eq!(port.config(), Some(&config));
+
+        let port = Port::open(&tty_name).unwrap();
+        assert_eq!(port.port_name(), Some(tty_name.as_str()));
+        assert_eq!(port.config(), Some(&PortConfig::default()));
+    }
+
+    #[test]
+    fn given_learn_mode_timeouts_then_cap_them_to_u32_millis() {
+        assert_eq!(millis_u32(Duration::from_secs(60)), 60_000);
+        assert_eq!(millis_u32(Duration::from_secs(u64::MAX)), u32::MAX);
+    }
+
+    #[test]
+    fn given_duty_cycle_reply_then_read_duty_cycle_limit() {
+        let mut responses = vec![];
+        ESP3Frame::assemble(0x02, &[0x00, 5, 10, 0x01, 0x68, 0x00, 0x78, 100], &[]).write_to(&mut responses).unwrap();
+        let mut port = Port::new(MockPort::new([responses]));
+
+        let duty_cycle = port.read_duty_cycle_limit().unwrap();
+        assert_eq!(duty_cycle.available, 5);
+        assert_eq!(duty_cycle.slot_left, 120);
+        assert_eq!(port.port.written()[6], 0x23);
     }
 
+    #[test]
+    fn given_usb300_version_reply_then_read_version_information() {
+        let mut data = vec![0x00, 2, 11, 1, 0, 2, 6, 3, 0, 0x04, 0x01, 0x93, 0x5e, 0x45, 0x4f, 0x01, 0x03];
+        data.extend_from_slice(b"GATEWAYCTRL\0\0\0\0\0");
+        let mut response = vec![];
+        ESP3Frame::assemble(0x02, &data, &[0x01]).write_to(&mut response).unwrap();
+        let mut port = Port::new(MockPort::new([response]));
+
+        let version = port.read_version_information().unwrap();
+        assert_eq!(version.description, "GATEWAYCTRL");
+        assert_eq!(version.chip_id, Address::from([0x04, 0x01, 0x93, 0x5e]));
+    }
+
+    #[test]
+    fn given_port_then_flush_it_and_flush_it_again_on_close() {
+        let mut mock = MockPort::default();
+        let mut port = Port::new(&mut mock);
+        port.write_frame(&Packet::CommonCommand(CommonCommand::ReadVersion).encode()).unwrap();
+        port.flush().unwrap();
+        port.close().unwrap();
+
+        assert_eq!(mock.flush_count(), 2);
+        assert!(!mock.written().is_empty());
+    }
+
+    #[test]
+    fn given_serial_ports_then_describe_them_enocean_gateways_first() {
+        let usb = |vid, pid, product: &str| SerialPortType::UsbPort(UsbPortInfo {
+            vid,
+            pid,
+            serial_number: None,
+            manufacturer: None,
+            product: Some(String::from(product)),
+        });
+        let ports = vec![
+            SerialPortInfo { port_name: String::from("/dev/ttyS0"), port_type: SerialPortType::Unknown },
+            SerialPortInfo { port_name: String::from("/dev/ttyACM0"), port_type: usb(0x2341, 0x0043, "Arduino Uno") },
+            SerialPortInfo { port_name: String::from("/dev/ttyUSB0"), port_type: usb(0x0403, 0x6001, "FT232R") },
+            SerialPortInfo { port_name: String::from("/dev/ttyUSB1"), port_type: usb(0x1234, 0x0001, "EnOcean USB 500") },
+        ];
+
+        let gateways = gateways_of(&ports);
+        let names: Vec<&str> = gateways.iter().map(|gateway| gateway.port_name.as_str()).collect();
+        assert_eq!(names, ["/dev/ttyUSB0", "/dev/ttyUSB1", "/dev/ttyS0", "/dev/ttyACM0"]);
+        assert!(gateways[0].is_likely_enocean() && gateways[1].is_likely_enocean());
+        assert!(!gateways[2].is_likely_enocean() && !gateways[3].is_likely_enocean());
+        assert_eq!(gateways[0].vid, Some(0x0403));
+        assert_eq!(gateways[2].vid, None);
+        assert_eq!(gateways[1].product.as_deref(), Some("EnOcean USB 500"));
+
+        assert_eq!(first_gateway(gateways).unwrap().port_name, "/dev/ttyUSB0");
+        assert_eq!(first_gateway(gateways_of(&ports[..2])), None);
+    }
 }
@@ -0,0 +1,66 @@
+//! Bit-field extraction helpers, for writing EEP parsers
+
+/// Util : get the value of a specific bit in a byte (bit 0 is the least significant one)
+pub fn bit_of_byte(bit_nb: u8, byte: &u8) -> bool {
+    ((byte >> bit_nb) & 1) != 0
+}
+
+/// Util : Byte to array of 8 bits conversion, most significant bit first
+pub fn bits_of_byte(byte: u8) -> [bool; 8] {
+    let mut value: [bool; 8] = [false; 8];
+    for i in 0..8 {
+        value[7 - i] = bit_of_byte(i as u8, &byte);
+    }
+    value
+}
+
+/// Value of the `len` bits field of `byte` starting at bit `start` (bit 0 is the least significant one)
+///
+/// # Panics
+/// If the field does not fit in the byte (`start + len > 8`)
+pub fn bits_range(byte: u8, start: u8, len: u8) -> u8 {
+    bits_range_of_bytes(&[byte], start, len) as u8
+}
+
+/// Value of the `len` bits field of the big endian `bytes` (eg. DB3..DB0 of a 4BS telegram),
+/// starting at bit `start` of the last byte (bit 0 is the least significant one of `bytes`)
+///
+/// # Panics
+/// If `bytes` is longer than 4 bytes, or if the field does not fit in them
+pub fn bits_range_of_bytes(bytes: &[u8], start: u8, len: u8) -> u32 {
+    assert!(bytes.len() <= 4, "at most 4 bytes can be read at once");
+    assert!(
+        usize::from(start) + usize::from(len) <= 8 * bytes.len(),
+        "the bit field does not fit in the bytes"
+    );
+    let value = bytes.iter().fold(0u64, |value, byte| (value << 8) | u64::from(*byte));
+    ((value >> start) & ((1u64 << len) - 1)) as u32
+}
+
+/// Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn given_byte_then_extract_a_3_bits_field() {
+        // DB0.5..DB0.3 of 0b0110_1100 is 0b101
+        assert_eq!(bits_range(0b0110_1100, 3, 3), 0b101);
+        assert_eq!(bits_range(0b0110_1100, 0, 8), 0b0110_1100);
+        assert!(bit_of_byte(3, &0b0110_1100));
+        assert_eq!(bits_of_byte(0b0110_1100), [false, true, true, false, true, true, false, false]);
+    }
+
+    #[test]
+    fn given_two_bytes_then_extract_a_12_bits_field_across_them() {
+        // Low nibble of the first byte followed by the whole second byte
+        assert_eq!(bits_range_of_bytes(&[0xa5, 0xbc], 0, 12), 0x5bc);
+        assert_eq!(bits_range_of_bytes(&[0x12, 0xa5, 0xbc, 0x00], 8, 12), 0x5bc);
+    }
+
+    #[test]
+    #[should_panic]
+    fn given_field_larger_than_byte_then_panic() {
+        bits_range(0xff, 6, 3);
+    }
+}